@@ -1,7 +1,7 @@
 use rust_i18n::t;
 
 // 初始化翻译
-rust_i18n::i18n!("../locales");
+rust_i18n::i18n!("locales");
 
 fn main() {
     // 设置当前语言
@@ -1,11 +1,11 @@
-use anyhow::Result;
+use anyhow::{Result, Context};
 use log::{info, warn};
 use regex::Regex;
 use serde::{Serialize, Deserialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use crate::config::Config;
-use crate::scanner::UsedKey;
+use crate::scanner::{KeyResolution, UsageContext, UsedKey};
 use crate::parser::DefinedKey;
 
 /// 翻译键分析结果
@@ -27,6 +27,51 @@ pub struct AnalysisResult {
     pub total_missing: usize,
     /// 动态键数量
     pub total_dynamic: usize,
+    /// 各语言的翻译完整度（与默认语言相同的值视为未翻译）
+    pub completeness: Vec<LanguageCompleteness>,
+    /// 与默认语言相同的未翻译值数量
+    pub total_untranslated: usize,
+    /// 只在当前 cfg 配置下被禁用的代码中使用的键
+    #[serde(default)]
+    pub conditional_keys: Vec<ConditionalKey>,
+    /// 只在禁用配置中使用的键数量
+    #[serde(default)]
+    pub total_conditional: usize,
+    /// 只在不计入使用的上下文（例如测试代码）中使用的键
+    #[serde(default)]
+    pub context_only_keys: Vec<ContextOnlyKey>,
+    /// 只在不计入使用的上下文中使用的键数量
+    #[serde(default)]
+    pub total_context_only: usize,
+    /// 使用次数最多的键
+    #[serde(default)]
+    pub most_used_keys: Vec<KeyUsageCount>,
+    /// 使用次数最少的键（不包括未使用的键）
+    #[serde(default)]
+    pub least_used_keys: Vec<KeyUsageCount>,
+}
+
+/// 使用热度中列出的键数量
+const HEATMAP_SIZE: usize = 10;
+
+/// 翻译键的使用次数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyUsageCount {
+    /// 翻译键
+    pub key: String,
+    /// 调用位置的数量
+    pub usage_count: usize,
+}
+
+/// 翻译键的一个调用位置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallSite {
+    /// 所在文件路径
+    pub file_path: String,
+    /// 所在行号
+    pub line_number: usize,
+    /// 所在列号
+    pub column: usize,
 }
 
 /// 未使用的翻译键
@@ -49,6 +94,52 @@ pub struct MissingKey {
     pub key: String,
     /// 缺少翻译的语言列表
     pub missing_languages: Vec<String>,
+    /// 第一个调用位置所在的文件路径
+    pub file_path: String,
+    /// 第一个调用位置所在的行号
+    pub line_number: usize,
+    /// 所有调用位置
+    #[serde(default)]
+    pub call_sites: Vec<CallSite>,
+    /// 严重程度
+    #[serde(default)]
+    pub severity: Severity,
+    /// 启用 minify_key 时，哈希键对应的原始消息文本
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// 缺少翻译的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// 普通：运行时可以回退到其他语言
+    #[default]
+    Normal,
+    /// 高：调用处通过 `locale = "..."` 显式指定了缺少翻译的语言
+    High,
+}
+
+/// 只在当前 cfg 配置下被禁用的代码中使用的键
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalKey {
+    /// 翻译键
+    pub key: String,
+    /// 禁用该使用位置的 cfg 条件
+    pub condition: String,
+    /// 所在文件路径
+    pub file_path: String,
+    /// 所在行号
+    pub line_number: usize,
+}
+
+/// 只在不计入使用的上下文（例如测试代码）中使用的键
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextOnlyKey {
+    /// 翻译键
+    pub key: String,
+    /// 使用位置所处的上下文
+    pub context: UsageContext,
     /// 所在文件路径
     pub file_path: String,
     /// 所在行号
@@ -64,9 +155,44 @@ pub struct DynamicKey {
     pub file_path: String,
     /// 所在行号
     pub line_number: usize,
+    /// 键模式的来源
+    #[serde(default)]
+    pub resolution: KeyResolution,
+    /// 仅因该模式而被视为已使用的翻译键
+    #[serde(default)]
+    pub matched_keys: Vec<String>,
+}
+
+/// 与默认语言完全相同的未翻译值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UntranslatedKey {
+    /// 翻译键
+    pub key: String,
+    /// 语言代码
+    pub language: String,
+    /// 翻译值（与默认语言相同）
+    pub value: String,
+    /// 所在文件路径
+    pub file_path: String,
+}
+
+/// 某种语言相对于默认语言的翻译完整度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageCompleteness {
+    /// 语言代码
+    pub language: String,
+    /// 默认语言中的翻译键数量
+    pub total_keys: usize,
+    /// 已翻译的键数量（存在且与默认语言不同，或属于允许相同的值）
+    pub translated_keys: usize,
+    /// 完整度百分比
+    pub percentage: f32,
+    /// 与默认语言相同的未翻译值
+    pub untranslated_keys: Vec<UntranslatedKey>,
 }
 
 /// 分析使用键和定义键，生成分析结果
+#[allow(clippy::unwrap_or_default)]
 pub fn analyze(
     used_keys: &[UsedKey],
     defined_keys: &[DefinedKey],
@@ -74,6 +200,61 @@ pub fn analyze(
 ) -> Result<AnalysisResult> {
     info!("正在分析翻译键使用情况");
     
+    // 启用 minify_key 时，t!() 中的消息文本按哈希键查找
+    let mut messages: HashMap<String, String> = HashMap::new();
+    let minified_keys: Vec<UsedKey>;
+    let used_keys = if config.minify_key.enabled {
+        minified_keys = used_keys.iter()
+            .map(|used_key| {
+                let mut used_key = used_key.clone();
+                let key = config.minify_key.key_for(&used_key.key);
+                if used_key.is_literal && key != used_key.key {
+                    messages.insert(key.clone(), std::mem::replace(&mut used_key.key, key));
+                }
+                used_key
+            })
+            .collect();
+        &minified_keys[..]
+    } else {
+        used_keys
+    };
+    
+    // 只在禁用配置中使用的键单独报告，不计入已使用
+    let (used_keys, disabled_keys): (Vec<&UsedKey>, Vec<&UsedKey>) = used_keys
+        .iter()
+        .partition(|k| k.disabled_by.is_none());
+    let enabled_keys: HashSet<&str> = used_keys.iter().map(|k| k.key.as_str()).collect();
+    let mut seen = HashSet::new();
+    let conditional_keys: Vec<ConditionalKey> = disabled_keys
+        .iter()
+        .filter(|k| !enabled_keys.contains(k.key.as_str()) && seen.insert(k.key.as_str()))
+        .map(|k| ConditionalKey {
+            key: k.key.clone(),
+            condition: k.disabled_by.clone().unwrap_or_default(),
+            file_path: k.file_path.clone(),
+            line_number: k.line_number,
+        })
+        .collect();
+    let mut separately_reported_keys: HashSet<&str> = conditional_keys.iter().map(|k| k.key.as_str()).collect();
+    
+    // 只在不计入使用的上下文（默认为测试、示例等）中使用的键同样单独报告
+    let (used_keys, excluded_keys): (Vec<&UsedKey>, Vec<&UsedKey>) = used_keys
+        .into_iter()
+        .partition(|k| config.count_contexts.contains(&k.context));
+    let counted_keys: HashSet<&str> = used_keys.iter().map(|k| k.key.as_str()).collect();
+    let mut seen = HashSet::new();
+    let context_only_keys: Vec<ContextOnlyKey> = excluded_keys
+        .iter()
+        .filter(|k| !counted_keys.contains(k.key.as_str()) && seen.insert(k.key.as_str()))
+        .map(|k| ContextOnlyKey {
+            key: k.key.clone(),
+            context: k.context,
+            file_path: k.file_path.clone(),
+            line_number: k.line_number,
+        })
+        .collect();
+    separately_reported_keys.extend(context_only_keys.iter().map(|k| k.key.as_str()));
+    
     // 提取所有使用的字面量键
     let literal_used_keys: HashSet<String> = used_keys
         .iter()
@@ -81,17 +262,26 @@ pub fn analyze(
         .map(|k| k.key.clone())
         .collect();
         
-    // 提取动态键
-    let dynamic_keys: Vec<DynamicKey> = used_keys
+    // 提取动态键，同一个模式只保留第一个使用位置
+    let mut seen = HashSet::new();
+    let mut dynamic_keys: Vec<DynamicKey> = used_keys
         .iter()
-        .filter(|k| !k.is_literal)
+        .filter(|k| !k.is_literal && seen.insert(k.key.as_str()))
         .map(|k| DynamicKey {
             pattern: k.key.clone(),
             file_path: k.file_path.clone(),
             line_number: k.line_number,
+            resolution: k.resolution,
+            matched_keys: Vec::new(),
         })
         .collect();
     
+    // 动态键模式按段匹配，预先编译；旧式的 {} 占位符等同于 *
+    let pattern_matchers: Vec<Option<Regex>> = dynamic_keys.iter()
+        .map(|dynamic_key| key_pattern_regex(&dynamic_key.pattern.replace("{}", "*")))
+        .collect();
+    let mut matched_keys: Vec<BTreeSet<String>> = vec![BTreeSet::new(); dynamic_keys.len()];
+    
     // 按语言分组的所有已定义键
     let mut defined_keys_by_language: HashMap<String, HashMap<String, DefinedKey>> = HashMap::new();
     
@@ -130,7 +320,9 @@ pub fn analyze(
         
         for (key, def_key) in keys {
             // 检查是否应该忽略这个键
-            let should_ignore = if let Some(re) = &ignore_regex {
+            // Fluent 术语（以 - 开头）只能在翻译文件内部被引用，不参与未使用检测
+            // 只在禁用配置或测试等上下文中使用的键已单独报告
+            let should_ignore = key.starts_with('-') || separately_reported_keys.contains(key.as_str()) || if let Some(re) = &ignore_regex {
                 re.is_match(key)
             } else {
                 false
@@ -140,24 +332,11 @@ pub fn analyze(
                 // 检查动态键模式是否匹配
                 let mut matched_by_dynamic = false;
                 
-                // 检查是否与任何动态键模式匹配
-                for dynamic_key in &dynamic_keys {
-                    // 简单的前缀匹配
-                    if key.starts_with(&dynamic_key.pattern) {
+                // 检查是否与任何动态键模式匹配，记录每个模式保留的键
+                for (index, matcher) in pattern_matchers.iter().enumerate() {
+                    if matcher.as_ref().is_some_and(|re| re.is_match(key)) {
                         matched_by_dynamic = true;
-                        break;
-                    }
-                    
-                    // 检查是否包含占位符 {}
-                    if dynamic_key.pattern.contains("{}") {
-                        // 将占位符替换为正则表达式模式 (.+)
-                        let pattern_regex_str = dynamic_key.pattern.replace("{}", "(.+)");
-                        if let Ok(re) = Regex::new(&pattern_regex_str) {
-                            if re.is_match(key) {
-                                matched_by_dynamic = true;
-                                break;
-                            }
-                        }
+                        matched_keys[index].insert(key.clone());
                     }
                 }
                 
@@ -178,18 +357,55 @@ pub fn analyze(
         }
     }
     
+    for (dynamic_key, keys) in dynamic_keys.iter_mut().zip(matched_keys) {
+        dynamic_key.matched_keys = keys.into_iter().collect();
+    }
+    
+    // 同一个键（以及显式指定的语言）的所有调用位置
+    let mut call_sites: HashMap<(&str, Option<&str>), Vec<CallSite>> = HashMap::new();
+    let mut first_usages: Vec<&UsedKey> = Vec::new();
+    for used_key in used_keys.iter().filter(|k| k.is_literal) {
+        let sites = call_sites.entry((used_key.key.as_str(), used_key.locale.as_deref())).or_default();
+        if sites.is_empty() {
+            first_usages.push(used_key);
+        }
+        sites.push(CallSite {
+            file_path: used_key.file_path.clone(),
+            line_number: used_key.line_number,
+            column: used_key.column,
+        });
+    }
+    
     // 找出缺少翻译的键
     let mut missing_keys = Vec::new();
     
-    for used_key in used_keys.iter().filter(|k| k.is_literal) {
+    for used_key in first_usages {
+        let sites = &call_sites[&(used_key.key.as_str(), used_key.locale.as_deref())];
+        let is_defined_in = |language: &str| defined_keys_by_language
+            .get(language)
+            .map(|keys| keys.contains_key(&used_key.key))
+            .unwrap_or(false);
+        
+        // 显式指定语言的调用只检查该语言，缺少翻译时为高严重程度
+        if let Some(locale) = &used_key.locale {
+            if !is_defined_in(locale) {
+                missing_keys.push(MissingKey {
+                    key: used_key.key.clone(),
+                    missing_languages: vec![locale.clone()],
+                    file_path: used_key.file_path.clone(),
+                    line_number: used_key.line_number,
+                    call_sites: sites.clone(),
+                    severity: Severity::High,
+                    message: messages.get(&used_key.key).cloned(),
+                });
+            }
+            continue;
+        }
+        
         let mut missing_languages = Vec::new();
         
         for language in &languages {
-            if !defined_keys_by_language
-                .get(language)
-                .map(|keys| keys.contains_key(&used_key.key))
-                .unwrap_or(false)
-            {
+            if !is_defined_in(language) {
                 missing_languages.push(language.clone());
             }
         }
@@ -200,10 +416,40 @@ pub fn analyze(
                 missing_languages,
                 file_path: used_key.file_path.clone(),
                 line_number: used_key.line_number,
+                call_sites: sites.clone(),
+                severity: Severity::Normal,
+                message: messages.get(&used_key.key).cloned(),
             });
         }
     }
     
+    // 高严重程度的缺失排在前面
+    missing_keys.sort_by_key(|k| std::cmp::Reverse(k.severity));
+    
+    // 使用热度：按调用位置数量统计每个字面量键
+    let mut usage_counts: HashMap<&str, usize> = HashMap::new();
+    for ((key, _), sites) in &call_sites {
+        *usage_counts.entry(key).or_default() += sites.len();
+    }
+    let mut usage_counts: Vec<KeyUsageCount> = usage_counts.into_iter()
+        .map(|(key, usage_count)| KeyUsageCount { key: key.to_string(), usage_count })
+        .collect();
+    usage_counts.sort_by(|a, b| b.usage_count.cmp(&a.usage_count).then_with(|| a.key.cmp(&b.key)));
+    let most_used_keys: Vec<KeyUsageCount> = usage_counts.iter().take(HEATMAP_SIZE).cloned().collect();
+    // 键较少时两个列表可以重叠
+    let mut least_used_keys: Vec<KeyUsageCount> = usage_counts.iter()
+        .rev()
+        .take(HEATMAP_SIZE)
+        .cloned()
+        .collect();
+    least_used_keys.sort_by(|a, b| a.usage_count.cmp(&b.usage_count).then_with(|| a.key.cmp(&b.key)));
+    
+    // 找出与默认语言相同的未翻译值
+    let completeness = analyze_completeness(&defined_keys_by_language, config)?;
+    let total_untranslated = completeness.iter()
+        .map(|c| c.untranslated_keys.len())
+        .sum();
+    
     // 计算统计信息
     let total_keys = defined_keys.len();
     let unused_percentage = if total_keys > 0 {
@@ -221,6 +467,14 @@ pub fn analyze(
         total_unused,
         total_missing: missing_keys.len(),
         total_dynamic: dynamic_keys.len(),
+        completeness,
+        total_untranslated,
+        total_conditional: conditional_keys.len(),
+        conditional_keys,
+        total_context_only: context_only_keys.len(),
+        context_only_keys,
+        most_used_keys,
+        least_used_keys,
     };
     
     info!("分析完成:");
@@ -228,7 +482,269 @@ pub fn analyze(
     info!("  未使用的翻译键数量: {}", result.total_unused);
     info!("  缺少翻译的键数量: {}", result.total_missing);
     info!("  动态键数量: {}", result.total_dynamic);
+    info!("  未翻译的值数量: {}", result.total_untranslated);
+    info!("  只在禁用配置中使用的键数量: {}", result.total_conditional);
+    info!("  只在测试等上下文中使用的键数量: {}", result.total_context_only);
     info!("  未使用翻译键百分比: {:.2}%", result.unused_percentage);
     
     Ok(result)
-} 
\ No newline at end of file
+}
+
+/// 对比每种语言与默认语言的翻译值，统计翻译完整度
+///
+/// 与默认语言逐字节相同的值视为未翻译，但以下情况除外：
+/// 不含任何字母的值（数字、符号、纯占位符）、`invariant_values` 中的值（例如品牌名）
+/// 以及键匹配 `invariant_keys` 中任一正则表达式的值。
+fn analyze_completeness(
+    defined_keys_by_language: &HashMap<String, HashMap<String, DefinedKey>>,
+    config: &Config,
+) -> Result<Vec<LanguageCompleteness>> {
+    let Some(default_keys) = defined_keys_by_language.get(&config.default_locale) else {
+        warn!("默认语言 {} 中没有任何翻译键，跳过未翻译值检查", config.default_locale);
+        return Ok(Vec::new());
+    };
+    
+    let invariant_key_regexes = config.invariant_keys.iter()
+        .map(|pattern| Regex::new(pattern)
+            .with_context(|| format!("不变键模式正则表达式无效: {}", pattern)))
+        .collect::<Result<Vec<_>>>()?;
+    
+    let is_invariant = |key: &str, value: &str| {
+        !value.chars().any(|c| c.is_alphabetic())
+            || config.invariant_values.iter().any(|v| v == value)
+            || invariant_key_regexes.iter().any(|re| re.is_match(key))
+    };
+    
+    let mut completeness = Vec::new();
+    
+    for (language, keys) in defined_keys_by_language {
+        if *language == config.default_locale {
+            continue;
+        }
+        
+        let mut translated_keys = 0;
+        let mut untranslated_keys = Vec::new();
+        
+        for (key, default_key) in default_keys {
+            let Some(def_key) = keys.get(key) else {
+                continue;
+            };
+            
+            if def_key.value == default_key.value && !is_invariant(key, &def_key.value) {
+                untranslated_keys.push(UntranslatedKey {
+                    key: key.clone(),
+                    language: language.clone(),
+                    value: def_key.value.clone(),
+                    file_path: def_key.file_path.clone(),
+                });
+            } else {
+                translated_keys += 1;
+            }
+        }
+        
+        untranslated_keys.sort_by(|a, b| a.key.cmp(&b.key));
+        
+        let total_keys = default_keys.len();
+        let percentage = if total_keys > 0 {
+            (translated_keys as f32 / total_keys as f32) * 100.0
+        } else {
+            100.0
+        };
+        
+        completeness.push(LanguageCompleteness {
+            language: language.clone(),
+            total_keys,
+            translated_keys,
+            percentage,
+            untranslated_keys,
+        });
+    }
+    
+    completeness.sort_by(|a, b| a.language.cmp(&b.language));
+    
+    Ok(completeness)
+}
+
+/// 将键模式转换为按段匹配的正则表达式
+///
+/// 键按 `.` 分段：单独成段的 `*` 匹配一个完整的段，`**` 匹配一个或多个段，
+/// 段内的 `*`（例如 `item_*`）匹配段内任意字符但不会跨越 `.`。
+/// 不含通配符的模式只匹配完全相同的键，因此 `user` 不会保留 `username.label` 或 `users.list`。
+fn key_pattern_regex(pattern: &str) -> Option<Regex> {
+    let segments: Vec<String> = pattern.split('.')
+        .map(|segment| {
+            if segment == "**" {
+                r"[^.]+(?:\.[^.]+)*".to_string()
+            } else if segment == "*" {
+                "[^.]+".to_string()
+            } else {
+                segment.split('*').map(regex::escape).collect::<Vec<_>>().join("[^.]*")
+            }
+        })
+        .collect();
+    
+    Regex::new(&format!("^{}$", segments.join(r"\."))).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_key_pattern_regex_matches_segments() {
+        let cases = [
+            ("user", "user", true),
+            ("user", "username.label", false),
+            ("user", "users.list", false),
+            ("status.*", "status.active", true),
+            ("status.*", "status.active.label", false),
+            ("status.*", "statuses.list", false),
+            ("item_*.label", "item_42.label", true),
+            ("item_*.label", "item_42.extra.label", false),
+            ("errors.**", "errors.not_found", true),
+            ("errors.**", "errors.http.not_found", true),
+            ("errors.**", "errors", false),
+            ("**.title", "auth.login.title", true),
+        ];
+
+        for (pattern, key, expected) in cases {
+            let re = key_pattern_regex(pattern).unwrap();
+            assert_eq!(re.is_match(key), expected, "模式 {} 与键 {}", pattern, key);
+        }
+    }
+
+    fn test_config() -> Config {
+        Config {
+            project_path: PathBuf::from("."),
+            src_dir: "src".to_string(),
+            locales_dir: "locales".to_string(),
+            extra_locales_dirs: Vec::new(),
+            template_dirs: Vec::new(),
+            default_locale: "en".to_string(),
+            threshold: 10.0,
+            ignore_pattern: None,
+            invariant_values: Vec::new(),
+            invariant_keys: Vec::new(),
+            evaluate_cfg: false,
+            features: Vec::new(),
+            cfg_options: Vec::new(),
+            count_contexts: vec![UsageContext::Production],
+            minify_key: Default::default(),
+            verbose: false,
+        }
+    }
+
+    fn defined_key(key: &str, language: &str, value: &str) -> DefinedKey {
+        DefinedKey {
+            key: key.to_string(),
+            language: language.to_string(),
+            value: value.to_string(),
+            file_path: format!("locales/{}.yml", language),
+        }
+    }
+
+    fn used_key(key: &str, line_number: usize) -> UsedKey {
+        UsedKey {
+            key: key.to_string(),
+            is_literal: true,
+            file_path: "src/main.rs".to_string(),
+            line_number,
+            column: 5,
+            resolution: KeyResolution::Literal,
+            disabled_by: None,
+            context: UsageContext::Production,
+            locale: None,
+        }
+    }
+
+    #[test]
+    fn test_analyze_keeps_every_call_site() {
+        let config = test_config();
+        let defined_keys = vec![DefinedKey {
+            key: "common.ok".to_string(),
+            language: "en".to_string(),
+            value: "OK".to_string(),
+            file_path: "locales/en.yml".to_string(),
+        }];
+        let used_keys = vec![
+            used_key("common.ok", 1),
+            used_key("common.ok", 2),
+            used_key("common.ok", 3),
+            used_key("user.name", 4),
+            used_key("user.name", 9),
+        ];
+
+        let result = analyze(&used_keys, &defined_keys, &config).unwrap();
+
+        assert_eq!(result.missing_keys.len(), 1);
+        let missing = &result.missing_keys[0];
+        assert_eq!(missing.key, "user.name");
+        assert_eq!(missing.line_number, 4);
+        assert_eq!(missing.call_sites.iter().map(|site| site.line_number).collect::<Vec<_>>(), vec![4, 9]);
+
+        assert_eq!(result.most_used_keys[0].key, "common.ok");
+        assert_eq!(result.most_used_keys[0].usage_count, 3);
+        assert_eq!(result.most_used_keys[1].usage_count, 2);
+        let least: Vec<(&str, usize)> = result.least_used_keys.iter().map(|k| (k.key.as_str(), k.usage_count)).collect();
+        assert_eq!(least, vec![("user.name", 2), ("common.ok", 3)]);
+    }
+
+    #[test]
+    fn test_analyze_explicit_locale_missing_is_high_severity() {
+        let defined_keys = vec![
+            defined_key("greeting", "en", "Hello"),
+            defined_key("farewell", "en", "Bye"),
+            defined_key("greeting", "fr", "Bonjour"),
+        ];
+        let mut explicit = used_key("greeting", 7);
+        explicit.locale = Some("de".to_string());
+        let used_keys = vec![used_key("farewell", 3), explicit];
+
+        let result = analyze(&used_keys, &defined_keys, &test_config()).unwrap();
+
+        let missing: Vec<(&str, &[String], Severity)> = result.missing_keys.iter()
+            .map(|k| (k.key.as_str(), k.missing_languages.as_slice(), k.severity))
+            .collect();
+        assert_eq!(missing, vec![
+            ("greeting", &["de".to_string()][..], Severity::High),
+            ("farewell", &["fr".to_string()][..], Severity::Normal),
+        ]);
+    }
+
+    #[test]
+    fn test_analyze_completeness() {
+        let mut config = test_config();
+        config.invariant_values = vec!["Acme".to_string()];
+        config.invariant_keys = vec![r"^nav\.".to_string()];
+        let defined_keys = vec![
+            defined_key("greeting", "en", "Hello"),
+            defined_key("brand", "en", "Acme"),
+            defined_key("count", "en", "42 / 100"),
+            defined_key("title", "en", "Dashboard"),
+            defined_key("nav.home", "en", "Home"),
+            defined_key("greeting", "fr", "Bonjour"),
+            defined_key("brand", "fr", "Acme"),
+            defined_key("count", "fr", "42 / 100"),
+            defined_key("title", "fr", "Dashboard"),
+            defined_key("nav.home", "fr", "Home"),
+            defined_key("greeting", "de", "Hello"),
+            defined_key("title", "de", "Übersicht"),
+        ];
+
+        let result = analyze(&[], &defined_keys, &config).unwrap();
+
+        // 默认语言本身不参与统计；缺少的键不算作未翻译
+        let summary: Vec<(&str, usize, usize, u32)> = result.completeness.iter()
+            .map(|c| (c.language.as_str(), c.total_keys, c.translated_keys, c.percentage.round() as u32))
+            .collect();
+        assert_eq!(summary, vec![("de", 5, 1, 20), ("fr", 5, 4, 80)]);
+        assert_eq!(result.completeness[1].untranslated_keys[0].key, "title");
+        assert_eq!(result.total_untranslated, 2);
+
+        // 默认语言中没有任何键时不做检查
+        let result = analyze(&[], &defined_keys[5..], &config).unwrap();
+        assert!(result.completeness.is_empty());
+        assert_eq!(result.total_untranslated, 0);
+    }
+}
@@ -0,0 +1,296 @@
+//! 以构建器方式配置并运行审计
+//!
+//! 命令行工具和库的使用者都通过 [`AuditBuilder`] 创建配置：构建时读取 cargo-i18n 的 `i18n.toml`
+//! 和每个 crate 的 `i18n!()` 调用，确定翻译文件目录、默认语言和 minify_key 设置。
+
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::{self, AnalysisResult};
+use crate::config::Config;
+use crate::error::Error;
+use crate::i18n_macro::{self, I18nInvocation};
+use crate::minify::{self, MinifyKeyOptions};
+use crate::parser::{self, DefinedKey};
+use crate::scanner::{self, UsageContext, UsedKey};
+
+/// 审计配置的构建器，默认值与命令行参数的默认值相同
+#[derive(Debug, Clone)]
+pub struct AuditBuilder {
+    config: Config,
+    default_locale: Option<String>,
+}
+
+impl AuditBuilder {
+    /// 以指定的项目根目录创建构建器
+    pub fn new(project_path: impl Into<PathBuf>) -> Self {
+        AuditBuilder {
+            config: Config {
+                project_path: project_path.into(),
+                src_dir: "src".to_string(),
+                locales_dir: "locales".to_string(),
+                extra_locales_dirs: Vec::new(),
+                template_dirs: Vec::new(),
+                default_locale: "en".to_string(),
+                threshold: 20.0,
+                ignore_pattern: None,
+                invariant_values: Vec::new(),
+                invariant_keys: Vec::new(),
+                evaluate_cfg: false,
+                features: Vec::new(),
+                cfg_options: Vec::new(),
+                count_contexts: vec![UsageContext::Production],
+                minify_key: MinifyKeyOptions::default(),
+                verbose: false,
+            },
+            default_locale: None,
+        }
+    }
+
+    /// 源代码目录，默认为 `src`
+    pub fn src_dir(mut self, dir: impl Into<String>) -> Self {
+        self.config.src_dir = dir.into();
+        self
+    }
+
+    /// 翻译文件目录，默认为 `locales`；项目中有 `i18n!()` 调用时以调用中的目录为准
+    pub fn locales_dir(mut self, dir: impl Into<String>) -> Self {
+        self.config.locales_dir = dir.into();
+        self
+    }
+
+    /// 额外的翻译文件目录（例如 Fluent 的 i18n 目录）
+    pub fn extra_locales_dirs<I: IntoIterator<Item = S>, S: Into<String>>(mut self, dirs: I) -> Self {
+        self.config.extra_locales_dirs.extend(dirs.into_iter().map(Into::into));
+        self
+    }
+
+    /// 模板目录，扫描其中 .html/.jinja/.tera 模板中的翻译调用
+    pub fn template_dirs<I: IntoIterator<Item = S>, S: Into<String>>(mut self, dirs: I) -> Self {
+        self.config.template_dirs.extend(dirs.into_iter().map(Into::into));
+        self
+    }
+
+    /// 默认语言（源语言）；未指定时使用 `i18n!()` 中的第一个 fallback 语言，否则为 `en`
+    pub fn default_locale(mut self, locale: impl Into<String>) -> Self {
+        self.default_locale = Some(locale.into());
+        self
+    }
+
+    /// 未使用翻译键的警告阈值百分比，默认为 20
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.config.threshold = threshold;
+        self
+    }
+
+    /// 忽略匹配指定模式的键（正则表达式）
+    pub fn ignore_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.config.ignore_pattern = Some(pattern.into());
+        self
+    }
+
+    /// 允许与默认语言相同的翻译值（例如品牌名）
+    pub fn invariant_values<I: IntoIterator<Item = S>, S: Into<String>>(mut self, values: I) -> Self {
+        self.config.invariant_values.extend(values.into_iter().map(Into::into));
+        self
+    }
+
+    /// 允许与默认语言相同的翻译键模式（正则表达式）
+    pub fn invariant_keys<I: IntoIterator<Item = S>, S: Into<String>>(mut self, patterns: I) -> Self {
+        self.config.invariant_keys.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// 是否根据 feature 和 cfg 选项求值 `cfg` 属性
+    pub fn evaluate_cfg(mut self, evaluate: bool) -> Self {
+        self.config.evaluate_cfg = evaluate;
+        self
+    }
+
+    /// 启用的 feature（隐含求值 `cfg` 属性）
+    pub fn features<I: IntoIterator<Item = S>, S: Into<String>>(mut self, features: I) -> Self {
+        self.config.features.extend(features.into_iter().map(Into::into));
+        self
+    }
+
+    /// cfg 选项，例如 `unix` 或 `target_os="windows"`（隐含求值 `cfg` 属性）
+    pub fn cfg_options<I: IntoIterator<Item = S>, S: Into<String>>(mut self, options: I) -> Self {
+        self.config.cfg_options.extend(options.into_iter().map(Into::into));
+        self
+    }
+
+    /// 计入“已使用”的使用上下文，默认只有正式代码
+    pub fn count_contexts(mut self, contexts: impl IntoIterator<Item = UsageContext>) -> Self {
+        self.config.count_contexts = contexts.into_iter().collect();
+        self
+    }
+
+    /// 详细输出模式
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.config.verbose = verbose;
+        self
+    }
+
+    /// 检查配置，并按 `i18n.toml` 和 `i18n!()` 调用补全配置
+    pub fn build(self) -> Result<Audit, Error> {
+        let mut config = self.config;
+        if !config.project_path.is_dir() {
+            return Err(Error::ProjectNotFound(config.project_path));
+        }
+        for pattern in config.ignore_pattern.iter().chain(&config.invariant_keys) {
+            Regex::new(pattern).map_err(|source| Error::InvalidPattern { pattern: pattern.clone(), source })?;
+        }
+        if let Some(locale) = &self.default_locale {
+            config.default_locale = locale.clone();
+        }
+
+        // 同时支持 Fluent：从 i18n.toml 中读取 Fluent 翻译文件目录
+        if let Some(assets_dir) = detect_fluent_assets_dir(&config.project_path)
+            && !config.extra_locales_dirs.contains(&assets_dir)
+        {
+            config.extra_locales_dirs.push(assets_dir);
+        }
+
+        // 解析 i18n!() 宏调用，确定翻译文件目录、fallback 语言和 minify_key 设置
+        let invocations = i18n_macro::find_invocations(&config.project_path)
+            .map_err(|e| Error::I18nMacro(e.into()))?;
+        apply_i18n_invocations(&mut config, &invocations, self.default_locale.is_some())?;
+
+        Ok(Audit { config })
+    }
+}
+
+/// 配置完成的审计
+#[derive(Debug, Clone)]
+pub struct Audit {
+    config: Config,
+}
+
+impl Audit {
+    /// 审计使用的配置
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// 取出审计使用的配置
+    pub fn into_config(self) -> Config {
+        self.config
+    }
+
+    /// 扫描源代码和模板，返回每一处翻译键的使用
+    pub fn scan_source_code(&self) -> Result<Vec<UsedKey>, Error> {
+        scanner::scan_source_code(&self.config).map_err(|e| Error::Scan(e.into()))
+    }
+
+    /// 解析所有翻译文件，返回已定义的翻译键
+    pub fn parse_translation_files(&self) -> Result<Vec<DefinedKey>, Error> {
+        parser::parse_translation_files(&self.config).map_err(|e| Error::Parse(e.into()))
+    }
+
+    /// 比对使用和定义，生成审计结果
+    pub fn analyze(&self, used_keys: &[UsedKey], defined_keys: &[DefinedKey]) -> Result<AnalysisResult, Error> {
+        analyzer::analyze(used_keys, defined_keys, &self.config).map_err(|e| Error::Analyze(e.into()))
+    }
+
+    /// 扫描、解析并分析，返回审计结果
+    pub fn run(&self) -> Result<AnalysisResult, Error> {
+        let used_keys = self.scan_source_code()?;
+        let defined_keys = self.parse_translation_files()?;
+        self.analyze(&used_keys, &defined_keys)
+    }
+}
+
+/// 读取 cargo-i18n 的 i18n.toml 配置，返回 Fluent 翻译文件目录
+fn detect_fluent_assets_dir(project_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(project_path.join("i18n.toml")).ok()?;
+    let value: toml::Value = toml::from_str(&content).ok()?;
+
+    value.get("fluent")?
+        .get("assets_dir")?
+        .as_str()
+        .map(|dir| dir.to_string())
+}
+
+/// 按 i18n!() 宏调用调整配置：翻译文件目录、默认语言和 minify_key 设置
+///
+/// 源代码目录所属 crate 的调用决定主翻译文件目录，工作区中其他 crate 的翻译文件目录作为额外目录。
+/// 调用指定的目录不存在时使用 `--locales-dir`，两者都不存在时返回错误。
+fn apply_i18n_invocations(
+    config: &mut Config,
+    invocations: &[I18nInvocation],
+    default_locale_given: bool,
+) -> Result<(), Error> {
+    let primary = i18n_macro::primary_invocation(invocations, &config.src_path());
+    let relative = |path: PathBuf| {
+        pathdiff::diff_paths(&path, &config.project_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    };
+
+    if let Some(primary) = primary {
+        let locales_dir = relative(primary.locales_path());
+        log::info!("检测到 i18n! 调用: {}，翻译文件目录: {}", primary.location(), locales_dir);
+        if primary.locales_path().is_dir() {
+            config.locales_dir = locales_dir;
+        } else if config.locales_path().is_dir() {
+            log::warn!(
+                "{} 中的 i18n! 调用指定的翻译文件目录不存在: {}，使用 {}",
+                primary.location(), locales_dir, config.locales_dir
+            );
+        } else {
+            return Err(Error::LocalesNotFound { location: primary.location(), path: primary.locales_path() });
+        }
+
+        // rust-i18n 在缺少翻译时回退到 fallback 语言，第一个 fallback 语言即源语言
+        if !default_locale_given
+            && let Some(fallback) = primary.fallback.first()
+        {
+            config.default_locale = fallback.clone();
+        }
+    }
+
+    for invocation in invocations.iter().filter(|i| Some(*i) != primary) {
+        let dir = relative(invocation.locales_path());
+        log::info!("检测到 i18n! 调用: {}，翻译文件目录: {}", invocation.location(), dir);
+        if dir != config.locales_dir && !config.extra_locales_dirs.contains(&dir) {
+            config.extra_locales_dirs.push(dir);
+        }
+    }
+
+    config.minify_key = minify::detect_options(config, primary);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_reports_typed_errors() {
+        let missing = AuditBuilder::new("does/not/exist").build();
+        assert!(matches!(missing, Err(Error::ProjectNotFound(path)) if path == Path::new("does/not/exist")));
+
+        let invalid = AuditBuilder::new(env!("CARGO_MANIFEST_DIR")).invariant_keys(["brand.("]).build();
+        assert!(matches!(invalid, Err(Error::InvalidPattern { pattern, .. }) if pattern == "brand.("));
+
+        let invalid = AuditBuilder::new(env!("CARGO_MANIFEST_DIR")).ignore_pattern("debug.[").build();
+        assert!(matches!(invalid, Err(Error::InvalidPattern { pattern, .. }) if pattern == "debug.["));
+    }
+
+    #[test]
+    fn test_build_checks_i18n_locales_dir() {
+        let project = tempfile::tempdir().unwrap();
+        fs::create_dir_all(project.path().join("src")).unwrap();
+        fs::write(project.path().join("src/main.rs"), "rust_i18n::i18n!(\"../locales\");\n").unwrap();
+
+        let result = AuditBuilder::new(project.path()).build();
+        assert!(matches!(result, Err(Error::LocalesNotFound { path, .. }) if path.ends_with("../locales")));
+
+        // 调用指定的目录不存在时使用 --locales-dir
+        fs::create_dir_all(project.path().join("i18n")).unwrap();
+        let config = AuditBuilder::new(project.path()).locales_dir("i18n").build().unwrap().into_config();
+        assert_eq!(config.locales_dir, "i18n");
+    }
+}
@@ -0,0 +1,168 @@
+//! 在构建脚本（build.rs）中运行审计
+//!
+//! 审计正在构建的 crate：为扫描的每个目录、源文件和翻译文件输出 `cargo:rerun-if-changed`，
+//! 为未使用的翻译键输出 `cargo:warning`，并可以在 `t!()` 引用了默认语言中不存在的键时让构建失败。
+
+use std::env;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::analyzer::AnalysisResult;
+use crate::audit::AuditBuilder;
+use crate::config::Config;
+use crate::error::Error;
+use crate::parser;
+use crate::scanner;
+
+/// 构建脚本中的审计
+///
+/// 在 build.rs 的 `main` 函数中：
+///
+/// ```no_run
+/// if let Err(e) = i18n_audit::BuildCheck::new().fail_on_missing(true).run() {
+///     eprintln!("{}", e);
+///     std::process::exit(1);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BuildCheck {
+    builder: AuditBuilder,
+    fail_on_missing: bool,
+    warn_unused: bool,
+}
+
+impl Default for BuildCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildCheck {
+    /// 审计正在构建的 crate（`CARGO_MANIFEST_DIR`），其余配置使用默认值
+    pub fn new() -> Self {
+        let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").map_or_else(|| PathBuf::from("."), PathBuf::from);
+        Self::with_builder(AuditBuilder::new(manifest_dir))
+    }
+
+    /// 使用自定义的审计配置
+    pub fn with_builder(builder: AuditBuilder) -> Self {
+        BuildCheck {
+            builder,
+            fail_on_missing: false,
+            warn_unused: true,
+        }
+    }
+
+    /// `t!()` 引用了默认语言中不存在的键时是否让构建失败，默认为否（只输出警告）
+    pub fn fail_on_missing(mut self, fail: bool) -> Self {
+        self.fail_on_missing = fail;
+        self
+    }
+
+    /// 是否为未使用的翻译键输出警告，默认为是
+    pub fn warn_unused(mut self, warn: bool) -> Self {
+        self.warn_unused = warn;
+        self
+    }
+
+    /// 运行审计并向标准输出写入 cargo 指令，返回审计结果
+    pub fn run(self) -> Result<AnalysisResult, Error> {
+        self.run_with_output(io::stdout().lock())
+    }
+
+    /// 运行审计并向指定的输出写入 cargo 指令，返回审计结果
+    pub fn run_with_output(self, mut output: impl Write) -> Result<AnalysisResult, Error> {
+        let audit = self.builder.build()?;
+        let result = audit.run()?;
+        let config = audit.config();
+
+        let instructions = cargo_instructions(config, &result, self.warn_unused);
+        for instruction in &instructions {
+            // 构建脚本的标准输出被 cargo 读取，写入失败时没有其他地方可以报告
+            let _ = writeln!(output, "{}", instruction);
+        }
+
+        let missing = missing_in_default_locale(&result, &config.default_locale);
+        if self.fail_on_missing && !missing.is_empty() {
+            return Err(Error::MissingKeys {
+                locale: config.default_locale.clone(),
+                keys: missing,
+            });
+        }
+        Ok(result)
+    }
+}
+
+/// 需要输出的 cargo 指令
+fn cargo_instructions(config: &Config, result: &AnalysisResult, warn_unused: bool) -> Vec<String> {
+    let mut instructions = Vec::new();
+
+    // 目录本身也要监视，以便新增的源文件、模板和翻译文件触发重新运行
+    let watched = scanner::source_roots(config).into_iter()
+        .chain(config.template_paths())
+        .chain(config.locales_paths())
+        .filter(|dir| dir.is_dir())
+        .chain(scanner::rust_source_files(config))
+        .chain(scanner::template_files(config))
+        .chain(config.locales_paths().into_iter().filter(|dir| dir.is_dir()).flat_map(|dir| parser::locale_files(&dir)));
+    for path in watched {
+        instructions.push(format!("cargo:rerun-if-changed={}", path.display()));
+    }
+
+    for missing in &result.missing_keys {
+        if missing.missing_languages.contains(&config.default_locale) {
+            instructions.push(format!(
+                "cargo:warning=翻译键 {} 在默认语言 {} 中不存在 ({}:{})",
+                missing.key, config.default_locale, missing.file_path, missing.line_number
+            ));
+        }
+    }
+
+    if warn_unused {
+        let mut unused: Vec<_> = result.unused_keys.values().flatten().collect();
+        unused.sort_by(|a, b| a.file_path.cmp(&b.file_path).then_with(|| a.key.cmp(&b.key)));
+        for key in unused {
+            instructions.push(format!("cargo:warning=未使用的翻译键 {} ({})", key.key, key.file_path));
+        }
+    }
+
+    instructions
+}
+
+/// 默认语言中不存在的键
+fn missing_in_default_locale(result: &AnalysisResult, default_locale: &str) -> Vec<String> {
+    result.missing_keys.iter()
+        .filter(|k| k.missing_languages.iter().any(|language| language == default_locale))
+        .map(|k| k.key.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_build_check_fails_on_missing_keys() {
+        let project = tempfile::tempdir().unwrap();
+        let project = project.path();
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::create_dir_all(project.join("locales")).unwrap();
+        fs::write(project.join("src/main.rs"), "fn main() {\n    t!(\"user.name\");\n    t!(\"user.email\");\n}\n").unwrap();
+        fs::write(project.join("locales/en.yml"), "user:\n  name: Name\n  age: Age\n").unwrap();
+
+        let check = || BuildCheck::with_builder(AuditBuilder::new(project));
+        let mut output = Vec::new();
+        check().run_with_output(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let rerun = |path: PathBuf| output.lines().any(|line| line == format!("cargo:rerun-if-changed={}", path.display()));
+        assert!(rerun(project.join("src")));
+        assert!(rerun(project.join("src/main.rs")));
+        assert!(rerun(project.join("locales")));
+        assert!(rerun(project.join("locales/en.yml")));
+        assert!(output.lines().any(|line| line.starts_with("cargo:warning=未使用的翻译键 user.age")));
+
+        let error = check().fail_on_missing(true).run_with_output(io::sink()).unwrap_err();
+        assert!(matches!(error, Error::MissingKeys { ref keys, .. } if keys == &["user.email"]));
+    }
+}
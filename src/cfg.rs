@@ -0,0 +1,486 @@
+//! `cfg` 属性的求值
+//!
+//! 根据命令行指定的 feature 和 cfg 选项，计算源文件中哪些代码区域在当前配置下被禁用，
+//! 以便把只在禁用配置中使用的翻译键单独报告；同时识别 `#[cfg(test)]` 和 `#[test]` 标记的测试代码区域。
+
+use log::debug;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::config::Config;
+use crate::syntax;
+
+/// 目标平台系列对应的无值选项，例如 `cfg(unix)`
+const TARGET_FAMILIES: [&str; 3] = ["unix", "windows", "wasm"];
+
+/// 用于求值 `cfg` 条件的配置集合
+#[derive(Debug, Clone, Default)]
+pub struct CfgSet {
+    /// 启用的 feature
+    features: HashSet<String>,
+    /// 设置的无值选项，例如 `unix`、`debug_assertions`
+    names: HashSet<String>,
+    /// 值已确定的无值选项：不在 `names` 中即为未启用，例如非当前平台系列的 `windows`
+    known_names: HashSet<String>,
+    /// 设置的键值选项，例如 `target_os = "windows"`
+    values: HashSet<(String, String)>,
+}
+
+impl CfgSet {
+    /// 根据配置创建 cfg 集合；未启用 cfg 求值时返回 None
+    ///
+    /// `debug_assertions` 默认启用。未通过 `--cfg` 指定目标平台时使用当前主机的 `target_os`，
+    /// 未指定 `target_family` 时根据 `target_os` 推断。
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if !config.evaluate_cfg && config.features.is_empty() && config.cfg_options.is_empty() {
+            return None;
+        }
+
+        let mut set = CfgSet::default();
+        set.names.insert("debug_assertions".to_string());
+        for feature in config.features.iter().flat_map(|f| f.split(',')) {
+            let feature = feature.trim();
+            if !feature.is_empty() {
+                set.features.insert(feature.to_string());
+            }
+        }
+
+        for option in &config.cfg_options {
+            match option.split_once('=') {
+                Some((name, value)) => {
+                    let name = name.trim();
+                    let value = value.trim().trim_matches('"');
+                    if name == "feature" {
+                        set.features.insert(value.to_string());
+                    } else {
+                        set.values.insert((name.to_string(), value.to_string()));
+                    }
+                }
+                None => {
+                    set.names.insert(option.trim().to_string());
+                }
+            }
+        }
+
+        let target_os = match set.value_of("target_os") {
+            Some(os) => os.to_string(),
+            None => {
+                set.values.insert(("target_os".to_string(), std::env::consts::OS.to_string()));
+                std::env::consts::OS.to_string()
+            }
+        };
+        let family = set.value_of("target_family").map(str::to_string)
+            .or_else(|| target_family(&target_os).map(str::to_string));
+        if let Some(family) = family {
+            set.values.insert(("target_family".to_string(), family.clone()));
+            set.names.insert(family);
+            set.known_names.extend(TARGET_FAMILIES.iter().map(|f| f.to_string()));
+        }
+
+        debug!("cfg 求值配置: {:?}", set);
+        Some(set)
+    }
+
+    /// 求值 `cfg(...)` 括号中的条件；无法识别的条件和没有配置的选项视为启用，避免误报
+    ///
+    /// `test` 的值不确定：`#[cfg(test)]` 和 `#[cfg(not(test))]` 都不算禁用，测试代码由使用上下文区分。
+    pub fn evaluate(&self, predicate: &str) -> bool {
+        let mut parser = PredicateParser { input: predicate, pos: 0 };
+        match parser.parse() {
+            Some(result) if parser.at_end() => self.evaluate_predicate(&result) != Some(false),
+            _ => true,
+        }
+    }
+
+    /// 求值条件，值不确定时返回 None
+    fn evaluate_predicate(&self, predicate: &Predicate) -> Option<bool> {
+        match predicate {
+            Predicate::Name(name) if name == "test" => None,
+            Predicate::Name(name) if self.names.contains(name) => Some(true),
+            Predicate::Name(name) => self.known_names.contains(name).then_some(false),
+            Predicate::Value(name, value) if name == "feature" => Some(self.features.contains(value)),
+            Predicate::Value(name, value) => self.values.iter()
+                .any(|(configured, _)| configured == name)
+                .then(|| self.values.contains(&(name.clone(), value.clone()))),
+            Predicate::All(items) => {
+                let results: Vec<_> = items.iter().map(|p| self.evaluate_predicate(p)).collect();
+                if results.contains(&Some(false)) {
+                    Some(false)
+                } else if results.contains(&None) {
+                    None
+                } else {
+                    Some(true)
+                }
+            }
+            Predicate::Any(items) => {
+                let results: Vec<_> = items.iter().map(|p| self.evaluate_predicate(p)).collect();
+                if results.contains(&Some(true)) {
+                    Some(true)
+                } else if results.contains(&None) {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+            Predicate::Not(inner) => self.evaluate_predicate(inner).map(|result| !result),
+        }
+    }
+
+    /// 键值选项设置的值
+    fn value_of(&self, name: &str) -> Option<&str> {
+        self.values.iter()
+            .find(|(configured, _)| configured == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// 根据 `target_os` 推断 `target_family`，无法确定时返回 None
+fn target_family(target_os: &str) -> Option<&'static str> {
+    match target_os {
+        "windows" => Some("windows"),
+        "linux" | "macos" | "ios" | "tvos" | "watchos" | "visionos" | "android" | "freebsd" | "netbsd"
+        | "openbsd" | "dragonfly" | "solaris" | "illumos" | "haiku" | "aix" | "redox" => Some("unix"),
+        "wasi" => Some("wasm"),
+        _ => None,
+    }
+}
+
+/// 被禁用的代码区域
+#[derive(Debug, Clone)]
+pub struct DisabledRegion {
+    /// 起始行号（从 1 开始，包含）
+    pub start_line: usize,
+    /// 结束行号（从 1 开始，包含）
+    pub end_line: usize,
+    /// 禁用该区域的 cfg 条件
+    pub condition: String,
+}
+
+/// 查找源文件中在当前配置下被禁用的代码区域
+///
+/// 支持 `#[cfg(...)]` 修饰的条目、语句和代码块，以及作用于整个文件的 `#![cfg(...)]`。
+pub fn disabled_regions(content: &str, cfg_set: &CfgSet) -> Vec<DisabledRegion> {
+    static CFG_ATTR_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"#(!?)\[\s*cfg\s*\(").unwrap());
+    let mut regions = Vec::new();
+
+    for m in CFG_ATTR_REGEX.find_iter(content) {
+        let open = m.end() - 1;
+        let Some(close) = syntax::find_matching_delimiter(content, open) else {
+            continue;
+        };
+        let predicate = content[open + 1..close].trim();
+        if cfg_set.evaluate(predicate) {
+            continue;
+        }
+
+        let condition = format!("cfg({})", predicate);
+        let is_inner = m.as_str().starts_with("#!");
+        let end = if is_inner {
+            Some(content.len())
+        } else {
+            // 属性之后的条目：到深度为 0 的 `;` 为止，或到第一个代码块的结束括号为止
+            let item_start = content[close..].find(']').map(|i| close + i + 1).unwrap_or(close);
+            item_end(content, item_start)
+        };
+
+        if let Some(end) = end {
+            let start_line = if is_inner { 1 } else { content[..m.start()].matches('\n').count() + 1 };
+            let end_line = content[..end.min(content.len())].matches('\n').count() + 1;
+            debug!("  禁用区域 {}-{}: {}", start_line, end_line, condition);
+            regions.push(DisabledRegion { start_line, end_line, condition });
+        }
+    }
+
+    regions
+}
+
+/// 查找源文件中被 cfg 禁用的子模块声明 `#[cfg(...)] mod name;`，返回 (模块文件所在目录, cfg 条件)
+///
+/// 例如 `src/main.rs` 中的 `#[cfg(feature = "pro")] mod pro;` 对应 `src/pro.rs` 或 `src/pro/` 目录。
+pub fn disabled_module_dirs(file_path: &str, content: &str, cfg_set: &CfgSet) -> Vec<(String, String)> {
+    cfg_module_dirs(file_path, content)
+        .into_iter()
+        .filter(|(_, predicate)| !cfg_set.evaluate(predicate))
+        .map(|(dir, predicate)| (dir, format!("cfg({})", predicate)))
+        .collect()
+}
+
+/// 查找源文件中的测试子模块声明 `#[cfg(test)] mod tests;`，返回模块文件所在目录
+pub fn test_module_dirs(file_path: &str, content: &str) -> Vec<String> {
+    cfg_module_dirs(file_path, content)
+        .into_iter()
+        .filter(|(_, predicate)| is_test_predicate(predicate))
+        .map(|(dir, _)| dir)
+        .collect()
+}
+
+/// 查找源文件中的测试代码区域：`#[cfg(test)]` 修饰的条目以及 `#[test]`、`#[tokio::test]` 等测试函数，
+/// 返回 (起始行号, 结束行号)，行号从 1 开始
+pub fn test_regions(content: &str) -> Vec<(usize, usize)> {
+    static TEST_ATTR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"#(!?)\[\s*(?:cfg\s*\(|(?:[A-Za-z_][A-Za-z0-9_]*::)*test\s*[\](])").unwrap()
+    });
+    let mut regions = Vec::new();
+
+    for m in TEST_ATTR_REGEX.find_iter(content) {
+        let is_inner = m.as_str().starts_with("#!");
+        let attr_open = m.start() + m.as_str().find('[').unwrap_or(0);
+        let Some(attr_close) = syntax::find_matching_delimiter(content, attr_open) else {
+            continue;
+        };
+
+        if m.as_str().contains("cfg") {
+            let open = m.end() - 1;
+            let Some(close) = syntax::find_matching_delimiter(content, open) else {
+                continue;
+            };
+            if !is_test_predicate(content[open + 1..close].trim()) {
+                continue;
+            }
+        }
+
+        let end = if is_inner { Some(content.len()) } else { item_end(content, attr_close + 1) };
+        if let Some(end) = end {
+            let start_line = if is_inner { 1 } else { content[..m.start()].matches('\n').count() + 1 };
+            let end_line = content[..end.min(content.len())].matches('\n').count() + 1;
+            regions.push((start_line, end_line));
+        }
+    }
+
+    regions
+}
+
+/// 判断 cfg 条件是否只在测试时启用，例如 `test` 或 `all(test, feature = "x")`
+fn is_test_predicate(predicate: &str) -> bool {
+    let mut parser = PredicateParser { input: predicate, pos: 0 };
+    match parser.parse() {
+        Some(result) if parser.at_end() => requires_test(&result),
+        _ => false,
+    }
+}
+
+/// 条件是否要求 `test` 启用：`test` 本身，或 `all(...)` 中有未取反的 `test`
+fn requires_test(predicate: &Predicate) -> bool {
+    match predicate {
+        Predicate::Name(name) => name == "test",
+        Predicate::All(predicates) => predicates.iter().any(requires_test),
+        _ => false,
+    }
+}
+
+/// 查找源文件中所有带 cfg 属性的子模块声明，返回 (模块文件所在目录, cfg 条件)
+fn cfg_module_dirs(file_path: &str, content: &str) -> Vec<(String, String)> {
+    static CFG_MOD_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"#\[\s*cfg\s*\(((?:[^\[\]]|\[[^\]]*\])*)\)\s*\](?:\s*#\[[^\]]*\])*\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_][A-Za-z0-9_]*)\s*;"
+        ).unwrap()
+    });
+
+    let path = Path::new(file_path);
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let module_dir = if matches!(stem, "main" | "lib" | "mod") {
+        parent.to_path_buf()
+    } else {
+        parent.join(stem)
+    };
+
+    CFG_MOD_REGEX.captures_iter(content)
+        .map(|caps| (module_dir.join(&caps[2]).to_string_lossy().to_string(), caps[1].trim().to_string()))
+        .collect()
+}
+
+/// 从 `start` 开始查找条目的结束位置：深度为 0 的 `;`，或第一个 `{` 对应的 `}`
+fn item_end(content: &str, start: usize) -> Option<usize> {
+    let semicolon = syntax::find_at_depth_zero(content, start, b';');
+    let brace = syntax::find_at_depth_zero(content, start, b'{');
+    match (semicolon, brace) {
+        (Some(semicolon), Some(brace)) if semicolon < brace => Some(semicolon),
+        (_, Some(brace)) => syntax::find_matching_delimiter(content, brace),
+        (semicolon, None) => semicolon,
+    }
+}
+
+/// cfg 条件的语法树
+#[derive(Debug)]
+enum Predicate {
+    Name(String),
+    Value(String, String),
+    All(Vec<Predicate>),
+    Any(Vec<Predicate>),
+    Not(Box<Predicate>),
+}
+
+/// cfg 条件的递归下降解析器
+struct PredicateParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl PredicateParser<'_> {
+    fn parse(&mut self) -> Option<Predicate> {
+        let name = self.identifier()?;
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some(')') {
+                        self.pos += 1;
+                        break;
+                    }
+                    items.push(self.parse()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.pos += 1,
+                        Some(')') => {}
+                        _ => return None,
+                    }
+                }
+                match name.as_str() {
+                    "all" => Some(Predicate::All(items)),
+                    "any" => Some(Predicate::Any(items)),
+                    "not" if items.len() == 1 => Some(Predicate::Not(Box::new(items.pop()?))),
+                    _ => None,
+                }
+            }
+            Some('=') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                let value = self.string()?;
+                Some(Predicate::Value(name, value))
+            }
+            _ => Some(Predicate::Name(name)),
+        }
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        if len == 0 {
+            return None;
+        }
+        self.pos += len;
+        Some(rest[..len].to_string())
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let rest = self.input[self.pos..].strip_prefix('"')?;
+        let end = rest.find('"')?;
+        self.pos += end + 2;
+        Some(rest[..end].to_string())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.pos >= self.input.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_regions() {
+        let cfg_set = CfgSet {
+            features: HashSet::from(["basic".to_string()]),
+            values: HashSet::from([("target_os".to_string(), "linux".to_string())]),
+            ..Default::default()
+        };
+
+        let content = r#"
+fn main() {
+    #[cfg(feature = "pro")]
+    println!("{}", t!("pro.banner"));
+
+    #[cfg(all(feature = "basic", not(target_os = "windows")))]
+    println!("{}", t!("basic.banner"));
+}
+
+#[cfg(any(target_os = "windows", feature = "pro"))]
+fn windows_only() {
+    println!("{}", t!("windows.title"));
+}
+"#;
+        let regions = disabled_regions(content, &cfg_set);
+
+        let lines: Vec<(usize, usize, &str)> = regions.iter()
+            .map(|r| (r.start_line, r.end_line, r.condition.as_str()))
+            .collect();
+        assert_eq!(lines, vec![
+            (3, 4, r#"cfg(feature = "pro")"#),
+            (10, 13, r#"cfg(any(target_os = "windows", feature = "pro"))"#),
+        ]);
+    }
+
+    #[test]
+    fn test_cfg_set_from_config() {
+        let config = |options: &[&str]| crate::audit::AuditBuilder::new(env!("CARGO_MANIFEST_DIR"))
+            .cfg_options(options.iter().copied())
+            .build()
+            .unwrap()
+            .into_config();
+
+        // test 由使用上下文区分，debug_assertions 默认启用，没有配置的选项视为启用
+        let host = CfgSet::from_config(&config(&["tokio_unstable"])).unwrap();
+        assert!(host.evaluate("not(test)"));
+        assert!(host.evaluate("test"));
+        assert!(host.evaluate("debug_assertions"));
+        assert!(!host.evaluate("not(debug_assertions)"));
+        assert!(host.evaluate("loom"));
+        assert!(host.evaluate("not(loom)"));
+        assert!(host.evaluate("tokio_unstable"));
+
+        // 平台系列根据 --cfg 指定的 target_os 推断
+        let windows = CfgSet::from_config(&config(&[r#"target_os="windows""#])).unwrap();
+        assert!(windows.evaluate("windows"));
+        assert!(windows.evaluate(r#"target_family = "windows""#));
+        assert!(!windows.evaluate("unix"));
+        assert!(!windows.evaluate(r#"target_family = "unix""#));
+        assert!(!windows.evaluate(r#"all(unix, not(test))"#));
+    }
+
+    #[test]
+    fn test_test_regions() {
+        let content = r#"
+fn main() {
+    println!("{}", t!("app.title"));
+}
+
+#[tokio::test]
+async fn smoke() {
+    assert_eq!(t!("smoke.title"), "Smoke");
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn it_works() {}
+}
+"#;
+        assert_eq!(test_regions(content), vec![(6, 9), (11, 15), (13, 14)]);
+
+        assert!(is_test_predicate("test"));
+        assert!(is_test_predicate(r#"all(test, feature = "notify")"#));
+        assert!(is_test_predicate(r#"all(unix, all(test, feature = "x"))"#));
+        assert!(!is_test_predicate("all(unix, not(test))"));
+        assert!(!is_test_predicate("any(test, loom)"));
+        assert!(!is_test_predicate("testing"));
+    }
+}
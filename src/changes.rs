@@ -0,0 +1,70 @@
+//! 收集对多个文件的修改，统一写入或生成补丁
+//!
+//! 提取硬编码字符串、重命名翻译键等命令会同时修改源代码和翻译文件，
+//! 先在内存中计算出所有修改，再决定写入文件还是输出供审阅的补丁。
+
+use anyhow::{Result, Context};
+use similar::TextDiff;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 一组文件修改：每个文件修改前后的内容
+#[derive(Debug, Default)]
+pub struct FileChanges {
+    files: BTreeMap<PathBuf, (String, String)>,
+}
+
+impl FileChanges {
+    /// 文件的当前内容：已修改的文件返回修改后的内容，否则读取文件（不存在时为空）
+    pub fn content(&self, path: &Path) -> String {
+        match self.files.get(path) {
+            Some((_, updated)) => updated.clone(),
+            None => fs::read_to_string(path).unwrap_or_default(),
+        }
+    }
+
+    /// 记录文件修改后的内容
+    pub fn update(&mut self, path: &Path, updated: String) {
+        match self.files.get_mut(path) {
+            Some((_, current)) => *current = updated,
+            None => {
+                let original = fs::read_to_string(path).unwrap_or_default();
+                self.files.insert(path.to_path_buf(), (original, updated));
+            }
+        }
+    }
+
+    /// 把所有修改写入文件
+    pub fn apply(&self) -> Result<()> {
+        for (path, (original, updated)) in &self.files {
+            if original == updated {
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("无法创建目录: {}", parent.display()))?;
+            }
+            fs::write(path, updated)
+                .with_context(|| format!("无法写入文件: {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// 生成所有修改的统一差异格式补丁，文件路径相对于项目根目录
+    pub fn unified_diff(&self, project_path: &Path) -> String {
+        let mut patch = String::new();
+        for (path, (original, updated)) in &self.files {
+            if original == updated {
+                continue;
+            }
+            let relative = path.strip_prefix(project_path).unwrap_or(path).display().to_string();
+            let diff = TextDiff::from_lines(original, updated);
+            patch.push_str(&diff.unified_diff()
+                .context_radius(3)
+                .header(&format!("a/{}", relative), &format!("b/{}", relative))
+                .to_string());
+        }
+        patch
+    }
+}
@@ -1,6 +1,9 @@
 use serde::{Serialize, Deserialize};
 use std::path::PathBuf;
 
+use crate::minify::MinifyKeyOptions;
+use crate::scanner::UsageContext;
+
 /// 应用程序配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -10,10 +13,30 @@ pub struct Config {
     pub src_dir: String,
     /// 翻译文件目录
     pub locales_dir: String,
+    /// 额外的翻译文件目录（例如 Fluent 的 i18n 目录）
+    pub extra_locales_dirs: Vec<String>,
+    /// 模板目录（askama、tera 等模板中的翻译调用）
+    pub template_dirs: Vec<String>,
+    /// 默认语言（源语言）
+    pub default_locale: String,
     /// 警告阈值百分比
     pub threshold: f32,
     /// 忽略模式（正则表达式）
     pub ignore_pattern: Option<String>,
+    /// 允许与默认语言相同的翻译值（例如品牌名）
+    pub invariant_values: Vec<String>,
+    /// 允许与默认语言相同的翻译键模式（正则表达式）
+    pub invariant_keys: Vec<String>,
+    /// 是否根据 feature 和 cfg 选项求值 `cfg` 属性
+    pub evaluate_cfg: bool,
+    /// 启用的 feature（可以用逗号分隔）
+    pub features: Vec<String>,
+    /// cfg 选项，例如 `unix` 或 `target_os="windows"`
+    pub cfg_options: Vec<String>,
+    /// 计入“已使用”的使用上下文，其余上下文中的使用单独报告
+    pub count_contexts: Vec<UsageContext>,
+    /// rust-i18n 的 minify_key 设置
+    pub minify_key: MinifyKeyOptions,
     /// 详细输出模式
     pub verbose: bool,
 }
@@ -28,4 +51,24 @@ impl Config {
     pub fn locales_path(&self) -> PathBuf {
         self.project_path.join(&self.locales_dir)
     }
-} 
\ No newline at end of file
+    
+    /// 获取所有翻译文件目录的完整路径，包括额外的翻译文件目录
+    pub fn locales_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.locales_path()];
+        for dir in &self.extra_locales_dirs {
+            let path = self.project_path.join(dir);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+    
+    /// 获取所有模板目录的完整路径
+    pub fn template_paths(&self) -> Vec<PathBuf> {
+        self.template_dirs.iter()
+            .map(|dir| self.project_path.join(dir))
+            .collect()
+    }
+}
+ 
\ No newline at end of file
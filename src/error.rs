@@ -0,0 +1,47 @@
+//! 库 API 的错误类型
+
+use std::path::PathBuf;
+
+/// 底层错误，保留完整的错误链
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// 审计过程中可能出现的错误，按出错的阶段区分
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// 项目根目录不存在
+    #[error("项目目录不存在: {0}")]
+    ProjectNotFound(PathBuf),
+
+    /// 配置中的正则表达式无效
+    #[error("无效的正则表达式: {pattern}")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+
+    /// 解析 `i18n!()` 宏调用失败，例如同一个 crate 中有相互冲突的调用
+    #[error("解析 i18n! 宏调用失败")]
+    I18nMacro(#[source] BoxError),
+
+    /// `i18n!()` 调用指定的翻译文件目录和 `--locales-dir` 都不存在
+    #[error("{location} 中的 i18n! 调用指定的翻译文件目录不存在: {}", .path.display())]
+    LocalesNotFound { location: String, path: PathBuf },
+
+    /// 扫描源代码失败
+    #[error("扫描源代码失败")]
+    Scan(#[source] BoxError),
+
+    /// 解析翻译文件失败
+    #[error("解析翻译文件失败")]
+    Parse(#[source] BoxError),
+
+    /// 分析翻译键使用情况失败
+    #[error("分析翻译键使用情况失败")]
+    Analyze(#[source] BoxError),
+
+    /// 构建脚本检查发现 `t!()` 引用了默认语言中不存在的键
+    #[error("默认语言 {locale} 中缺少 {} 个翻译键: {}", .keys.len(), .keys.join(", "))]
+    MissingKeys { locale: String, keys: Vec<String> },
+}
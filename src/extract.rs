@@ -0,0 +1,607 @@
+//! 查找绕过 `t!()` 的硬编码用户可见字符串
+//!
+//! 在可配置的宏（`println!`、`write!` 等）和函数（`label()` 等）的参数中查找字符串字面量，
+//! 为每个字符串建议一个翻译键，并可以把它写入默认语言的翻译文件，或者直接把调用改写为 `t!()`。
+
+use anyhow::{Result, Context};
+use log::{debug, info};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::cfg;
+use crate::changes::FileChanges;
+use crate::config::Config;
+use crate::locale_writer;
+use crate::minify;
+use crate::parser::DefinedKey;
+use crate::syntax;
+
+/// 默认检查的宏
+pub const DEFAULT_MACROS: [&str; 6] = ["println", "print", "eprintln", "eprint", "write", "writeln"];
+/// 默认检查的函数或方法
+pub const DEFAULT_FUNCTIONS: [&str; 4] = ["label", "button", "heading", "set_title"];
+/// 第一个参数是写入目标而不是格式字符串的宏
+const WRITER_MACROS: [&str; 2] = ["write", "writeln"];
+/// 建议的键名最多包含的单词数
+const MAX_KEY_WORDS: usize = 4;
+/// 在同一行或上一行的注释中添加该标记可以忽略硬编码字符串
+const IGNORE_MARKER: &str = "i18n-audit: ignore";
+
+/// 硬编码字符串的检查范围
+#[derive(Debug, Clone)]
+pub struct ExtractOptions {
+    /// 检查的宏名称（不含 `!`）
+    pub macros: Vec<String>,
+    /// 检查的函数或方法名称
+    pub functions: Vec<String>,
+    /// 只检查这些文件或目录（相对于项目根目录），为空时检查整个源代码目录
+    pub paths: Vec<PathBuf>,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        ExtractOptions {
+            macros: DEFAULT_MACROS.map(String::from).to_vec(),
+            functions: DEFAULT_FUNCTIONS.map(String::from).to_vec(),
+            paths: Vec::new(),
+        }
+    }
+}
+
+/// 一个没有使用 `t!()` 的硬编码字符串
+#[derive(Debug, Clone, Serialize)]
+pub struct HardcodedString {
+    /// 字符串字面量的内容
+    pub text: String,
+    /// 文件路径（相对于项目根目录）
+    pub file_path: String,
+    /// 行号
+    pub line_number: usize,
+    /// 所在的宏或函数，例如 `println!` 或 `label()`
+    pub call: String,
+    /// 建议的翻译键
+    pub suggested_key: String,
+    /// 写入翻译文件的值，格式参数转换为 rust-i18n 的 `%{name}` 占位符
+    pub value: String,
+    /// 占位符名称及对应的格式参数表达式
+    pub arguments: Vec<(String, String)>,
+    /// 格式参数是否带有格式说明（例如 `{:.2}`），这类参数无法原样转换为占位符
+    pub has_format_spec: bool,
+    /// 默认语言中是否已经存在相同的翻译
+    pub already_defined: bool,
+    /// 字符串字面量在文件中的字节范围
+    #[serde(skip)]
+    pub literal_span: Range<usize>,
+    /// 宏调用的参数列表在文件中的字节范围（不含括号），改写时整体替换
+    #[serde(skip)]
+    pub args_span: Range<usize>,
+    /// `write!` 等宏的写入目标参数
+    #[serde(skip)]
+    pub writer: Option<String>,
+}
+
+/// 格式字符串转换为翻译值的结果
+#[derive(Debug, PartialEq, Eq)]
+struct FormatMessage {
+    value: String,
+    arguments: Vec<(String, String)>,
+    has_format_spec: bool,
+}
+
+/// 扫描源代码目录，查找硬编码字符串并为其建议翻译键
+pub fn find_hardcoded_strings(
+    config: &Config,
+    options: &ExtractOptions,
+    defined_keys: &[DefinedKey],
+) -> Result<Vec<HardcodedString>> {
+    let src_path = config.src_path();
+    info!("正在查找硬编码字符串: {}", src_path.display());
+
+    let mut found = Vec::new();
+    for entry in WalkDir::new(&src_path)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
+        .filter(|e| options.paths.is_empty() || options.paths.iter()
+            .any(|p| e.path().starts_with(config.project_path.join(p))))
+    {
+        let path = entry.path();
+        debug!("处理文件: {}", path.display());
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("无法读取文件: {}", path.display()))?;
+        let relative_path = path.strip_prefix(&config.project_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        let module_prefix = module_prefix(path.strip_prefix(&src_path).unwrap_or(path));
+
+        for mut hardcoded in scan_content(&content, &relative_path, options) {
+            hardcoded.suggested_key = match module_prefix.is_empty() {
+                true => key_slug(&hardcoded.value),
+                false => format!("{}.{}", module_prefix, key_slug(&hardcoded.value)),
+            };
+            found.push(hardcoded);
+        }
+    }
+
+    assign_unique_keys(&mut found, defined_keys, &config.default_locale);
+
+    info!("找到 {} 个硬编码字符串", found.len());
+    Ok(found)
+}
+
+/// 提取硬编码字符串需要对文件做的修改
+#[derive(Debug, Default)]
+pub struct ExtractionPlan {
+    /// 对源代码和翻译文件的修改
+    pub changes: FileChanges,
+    /// 改写为 `t!()` 的字符串数量
+    pub rewritten: usize,
+    /// 写入默认语言翻译文件的键数量
+    pub added_keys: usize,
+    /// 因带有格式说明而无法自动改写的字符串
+    pub skipped: Vec<HardcodedString>,
+}
+
+/// 计算把尚未定义的字符串以建议的键写入默认语言翻译文件所需的修改
+pub fn plan_locale_changes(
+    found: &[HardcodedString],
+    defined_keys: &[DefinedKey],
+    config: &Config,
+) -> Result<ExtractionPlan> {
+    let mut plan = ExtractionPlan::default();
+    add_locale_changes(&mut plan, found, defined_keys, config)?;
+    Ok(plan)
+}
+
+/// 计算把硬编码字符串改写为 `t!()` 调用，并把原文写入默认语言翻译文件所需的修改
+///
+/// 格式参数转换为 `t!("key", name = expr)` 的具名参数；带有格式说明（例如 `{:.2}`）的字符串不改写。
+pub fn plan_extraction(
+    found: &[HardcodedString],
+    defined_keys: &[DefinedKey],
+    config: &Config,
+) -> Result<ExtractionPlan> {
+    let mut plan = ExtractionPlan::default();
+    let (rewritable, skipped): (Vec<&HardcodedString>, Vec<&HardcodedString>) = found.iter()
+        .partition(|h| !h.has_format_spec && h.arguments.iter().all(|(_, expr)| !expr.is_empty()));
+    plan.skipped = skipped.into_iter().cloned().collect();
+
+    let mut by_file: BTreeMap<&str, Vec<&HardcodedString>> = BTreeMap::new();
+    for hardcoded in &rewritable {
+        by_file.entry(hardcoded.file_path.as_str()).or_default().push(hardcoded);
+    }
+
+    for (file_path, items) in by_file {
+        let path = config.project_path.join(file_path);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("无法读取文件: {}", path.display()))?;
+        plan.changes.update(&path, rewrite_content(&content, &items));
+        plan.rewritten += items.len();
+    }
+
+    let rewritable: Vec<HardcodedString> = rewritable.into_iter().cloned().collect();
+    add_locale_changes(&mut plan, &rewritable, defined_keys, config)?;
+    Ok(plan)
+}
+
+/// 把尚未定义的字符串加入默认语言翻译文件的修改中
+fn add_locale_changes(
+    plan: &mut ExtractionPlan,
+    found: &[HardcodedString],
+    defined_keys: &[DefinedKey],
+    config: &Config,
+) -> Result<()> {
+    let mut written: HashSet<&str> = HashSet::new();
+    for hardcoded in found.iter().filter(|h| !h.already_defined) {
+        if !written.insert(&hardcoded.suggested_key) {
+            continue;
+        }
+        let path = locale_writer::locate_target_file(
+            &hardcoded.suggested_key,
+            &config.default_locale,
+            defined_keys,
+            config,
+        );
+
+        let updated = locale_writer::with_key(
+            &path,
+            &plan.changes.content(&path),
+            &config.default_locale,
+            &hardcoded.suggested_key,
+            &hardcoded.value,
+        )?;
+        plan.changes.update(&path, updated);
+    }
+    plan.added_keys = written.len();
+    Ok(())
+}
+
+/// 把文件中的硬编码字符串改写为 `t!()` 调用，必要时导入 `rust_i18n::t`
+fn rewrite_content(content: &str, items: &[&HardcodedString]) -> String {
+    let mut edits: Vec<(Range<usize>, String)> = items.iter()
+        .map(|hardcoded| {
+            let mut call = format!("t!(\"{}\"", hardcoded.suggested_key);
+            for (name, expr) in &hardcoded.arguments {
+                call.push_str(&format!(", {} = {}", name, expr));
+            }
+            call.push(')');
+
+            if hardcoded.call.ends_with('!') {
+                // println!("Hello, {}!", name) => println!("{}", t!("key", name = name))
+                let writer = hardcoded.writer.as_ref().map(|w| format!("{}, ", w)).unwrap_or_default();
+                (hardcoded.args_span.clone(), format!("{}\"{{}}\", {}", writer, call))
+            } else {
+                (hardcoded.literal_span.clone(), call)
+            }
+        })
+        .collect();
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+
+    let mut updated = content.to_string();
+    for (range, replacement) in edits {
+        updated.replace_range(range, &replacement);
+    }
+
+    let import_regex = Regex::new(r"\brust_i18n::(?:t\b|\*|\{[^}]*\bt\b)").unwrap();
+    let t_macro_regex = Regex::new(r"\bt!\s*\(").unwrap();
+    if !import_regex.is_match(content) && !t_macro_regex.is_match(content) {
+        // 导入放在文件开头的内部属性和模块文档之后
+        let insert_at = updated.lines()
+            .take_while(|line| line.starts_with("//!") || line.starts_with("#![") || line.trim().is_empty())
+            .map(|line| line.len() + 1)
+            .sum::<usize>()
+            .min(updated.len());
+        updated.insert_str(insert_at, "use rust_i18n::t;\n");
+    }
+
+    updated
+}
+
+/// 扫描一个文件中的硬编码字符串（尚未分配翻译键）
+fn scan_content(content: &str, file_path: &str, options: &ExtractOptions) -> Vec<HardcodedString> {
+    let names = |names: &[String]| names.iter().map(|n| regex::escape(n)).collect::<Vec<_>>().join("|");
+    let call_regex = match (options.macros.is_empty(), options.functions.is_empty()) {
+        (true, true) => return Vec::new(),
+        (false, true) => format!(r"\b(?:({})!)\s*\(", names(&options.macros)),
+        (true, false) => format!(r"\b(?:({}))\s*\(", names(&options.functions)),
+        (false, false) => format!(r"\b(?:({})!|({}))\s*\(", names(&options.macros), names(&options.functions)),
+    };
+    let call_regex = Regex::new(&call_regex).unwrap();
+    let fn_decl_regex = Regex::new(r"\bfn\s+$").unwrap();
+
+    let lines: Vec<&str> = content.lines().collect();
+    let test_regions = cfg::test_regions(content);
+    let mut found = Vec::new();
+
+    for caps in call_regex.captures_iter(content) {
+        let m = caps.get(0).unwrap();
+        let line_start = content[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+        if content[line_start..m.start()].trim_start().starts_with("//")
+            || fn_decl_regex.is_match(&content[line_start..m.start()])
+        {
+            continue;
+        }
+
+        let (name, is_macro) = match (caps.get(1), caps.get(2)) {
+            (Some(name), _) if !options.macros.is_empty() => (name.as_str(), true),
+            (Some(name), _) | (None, Some(name)) => (name.as_str(), false),
+            (None, None) => continue,
+        };
+
+        let open = m.end() - 1;
+        let Some(close) = syntax::find_matching_delimiter(content, open) else {
+            continue;
+        };
+        let args = argument_spans(content, open + 1..close);
+
+        // write!(f, "...") 的第一个参数是写入目标
+        let format_index = if is_macro && WRITER_MACROS.contains(&name) { 1 } else { 0 };
+        let Some(format_arg) = args.get(format_index) else {
+            continue;
+        };
+        let Some((literal_span, text)) = whole_string_literal(content, format_arg.clone()) else {
+            continue;
+        };
+
+        let line_number = content[..literal_span.start].matches('\n').count() + 1;
+        let ignored = lines.get(line_number - 1).is_some_and(|line| line.contains(IGNORE_MARKER))
+            || (line_number >= 2 && lines.get(line_number - 2)
+                .is_some_and(|line| line.trim_start().starts_with("//") && line.contains(IGNORE_MARKER)));
+        let in_test = test_regions.iter().any(|(start, end)| *start <= line_number && line_number <= *end);
+        if ignored || in_test || !has_visible_text(&text, is_macro) {
+            continue;
+        }
+
+        // 只有宏的格式字符串带有格式参数，函数参数按普通文本处理
+        let message = if is_macro {
+            let format_args: Vec<&str> = args[format_index + 1..].iter()
+                .map(|span| content[span.clone()].trim())
+                .collect();
+            convert_format_string(&text, &format_args)
+        } else {
+            FormatMessage { value: text.clone(), arguments: Vec::new(), has_format_spec: false }
+        };
+
+        found.push(HardcodedString {
+            text,
+            file_path: file_path.to_string(),
+            line_number,
+            call: if is_macro { format!("{}!", name) } else { format!("{}()", name) },
+            suggested_key: String::new(),
+            value: message.value,
+            arguments: message.arguments,
+            has_format_spec: message.has_format_spec,
+            already_defined: false,
+            literal_span,
+            args_span: open + 1..close,
+            writer: (format_index > 0).then(|| content[args[0].clone()].trim().to_string()),
+        });
+    }
+
+    found
+}
+
+/// 按深度为 0 的逗号拆分参数列表，返回每个非空参数的字节范围
+fn argument_spans(content: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let inner = &content[range.clone()];
+    let mut spans = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = syntax::find_at_depth_zero(inner, start, b',').unwrap_or(inner.len());
+        if !inner[start..end].trim().is_empty() {
+            spans.push(range.start + start..range.start + end);
+        }
+        if end >= inner.len() {
+            break;
+        }
+        start = end + 1;
+    }
+    spans
+}
+
+/// 如果参数恰好是一个字符串字面量，返回字面量的字节范围及其内容
+fn whole_string_literal(content: &str, span: Range<usize>) -> Option<(Range<usize>, String)> {
+    let arg = &content[span.clone()];
+    let offset = span.start + (arg.len() - arg.trim_start().len());
+    let arg = arg.trim();
+    match syntax::string_literal_spans(arg).as_slice() {
+        [(range, value)] if range.start == 0 && range.end == arg.len() => {
+            Some((offset..offset + arg.len(), value.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// 判断字符串（去掉格式占位符后）是否包含用户可见的文字
+fn has_visible_text(text: &str, is_format_string: bool) -> bool {
+    let placeholder_regex = Regex::new(r"\{[^{}]*\}").unwrap();
+    let text = if is_format_string { placeholder_regex.replace_all(text, "") } else { text.into() };
+    text.chars().any(|c| c.is_alphabetic())
+}
+
+/// 把格式字符串转换为 rust-i18n 的翻译值：`{}`、`{0}`、`{name}` 转换为 `%{name}` 占位符
+///
+/// 位置参数是简单的变量或字段访问时以最后一段作为占位符名称，否则使用 `argN`。
+fn convert_format_string(format: &str, args: &[&str]) -> FormatMessage {
+    let named_arg_regex = Regex::new(r"^([A-Za-z_][A-Za-z0-9_]*)\s*=([^=].*)$").unwrap();
+    let simple_expr_regex = Regex::new(r"^&?(?:[A-Za-z_][A-Za-z0-9_]*\.)*([A-Za-z_][A-Za-z0-9_]*)$").unwrap();
+
+    let mut positional = Vec::new();
+    let mut named = HashMap::new();
+    for arg in args {
+        match named_arg_regex.captures(arg) {
+            Some(caps) => {
+                named.insert(caps[1].to_string(), caps[2].trim().to_string());
+            }
+            None => positional.push(arg.to_string()),
+        }
+    }
+
+    let mut value = String::new();
+    let mut arguments: Vec<(String, String)> = Vec::new();
+    let mut has_format_spec = false;
+    let mut next_positional = 0;
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                value.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                value.push('}');
+            }
+            '{' => {
+                let mut inner = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    inner.push(c);
+                }
+                let (reference, spec) = inner.split_once(':').unwrap_or((&inner, ""));
+                has_format_spec |= !spec.is_empty();
+
+                let reference = reference.trim();
+                let (name, expr) = if reference.is_empty() || reference.chars().all(|c| c.is_ascii_digit()) {
+                    let index = reference.parse().unwrap_or_else(|_| {
+                        next_positional += 1;
+                        next_positional - 1
+                    });
+                    let expr = positional.get(index).cloned().unwrap_or_default();
+                    let name = simple_expr_regex.captures(&expr)
+                        .map(|caps| caps[1].to_string())
+                        .unwrap_or_else(|| format!("arg{}", index));
+                    (name, expr)
+                } else {
+                    // 具名参数，或者直接捕获的同名变量
+                    let expr = named.get(reference).cloned().unwrap_or_else(|| reference.to_string());
+                    (reference.to_string(), expr)
+                };
+
+                // 同名但表达式不同的参数使用不同的占位符
+                let mut unique_name = name.clone();
+                let mut suffix = 2;
+                while arguments.iter().any(|(n, e)| *n == unique_name && *e != expr) {
+                    unique_name = format!("{}{}", name, suffix);
+                    suffix += 1;
+                }
+                if !arguments.iter().any(|(n, _)| *n == unique_name) {
+                    arguments.push((unique_name.clone(), expr));
+                }
+                value.push_str(&format!("%{{{}}}", unique_name));
+            }
+            c => value.push(c),
+        }
+    }
+
+    FormatMessage { value, arguments, has_format_spec }
+}
+
+/// 根据源文件相对于源代码目录的路径生成键前缀，例如 `commands/user.rs` 对应 `commands.user`
+fn module_prefix(relative_path: &Path) -> String {
+    let mut segments: Vec<String> = relative_path.with_extension("")
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .map(|s| s.to_string())
+        .collect();
+    if segments.last().is_some_and(|last| matches!(last.as_str(), "main" | "lib" | "mod")) {
+        segments.pop();
+    }
+    segments.join(".")
+}
+
+/// 根据翻译值生成键名的最后一段，例如 `Failed to load %{path}` 对应 `failed_to_load`
+fn key_slug(value: &str) -> String {
+    let placeholder_regex = Regex::new(r"%\{[^}]*\}").unwrap();
+    let text = placeholder_regex.replace_all(value, " ");
+    let words: Vec<String> = text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(MAX_KEY_WORDS)
+        .map(|w| w.to_ascii_lowercase())
+        .collect();
+
+    match words.first() {
+        // 键的每一段不以数字开头
+        Some(first) if !first.starts_with(|c: char| c.is_ascii_digit()) => words.join("_"),
+        Some(_) => format!("msg_{}", words.join("_")),
+        // 没有 ASCII 单词（例如中文文本）时使用文本的哈希
+        None => format!("msg_{}", minify::minify_key(value, 8, "", 0).to_ascii_lowercase()),
+    }
+}
+
+/// 为建议的键去重：与默认语言中值相同的键直接复用，冲突的键追加数字后缀
+fn assign_unique_keys(found: &mut [HardcodedString], defined_keys: &[DefinedKey], default_locale: &str) {
+    let mut assigned: HashMap<String, String> = defined_keys.iter()
+        .filter(|k| k.language == default_locale)
+        .map(|k| (k.key.clone(), k.value.clone()))
+        .collect();
+    let existing_by_value: HashMap<&str, &str> = defined_keys.iter()
+        .filter(|k| k.language == default_locale)
+        .map(|k| (k.value.as_str(), k.key.as_str()))
+        .collect();
+
+    for hardcoded in found.iter_mut() {
+        if let Some(key) = existing_by_value.get(hardcoded.value.as_str()) {
+            hardcoded.suggested_key = key.to_string();
+            hardcoded.already_defined = true;
+            continue;
+        }
+
+        let base = hardcoded.suggested_key.clone();
+        let mut suffix = 2;
+        while assigned.get(&hardcoded.suggested_key).is_some_and(|value| *value != hardcoded.value) {
+            hardcoded.suggested_key = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        assigned.insert(hardcoded.suggested_key.clone(), hardcoded.value.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_content() {
+        let content = r#"
+fn main() {
+    println!("Hello, {}!", user.name);
+    println!("{}", t!("greetings.hello"));
+    println!("{:?}", value);
+    eprintln!("Failed to open {path}: {}", err);
+    // println!("commented out");
+    println!("debug only"); // i18n-audit: ignore
+    ui.label("Settings");
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Not found: {}", self.0)
+    }
+}
+
+#[test]
+fn test() {
+    println!("test output");
+}
+"#;
+        let found = scan_content(content, "src/main.rs", &ExtractOptions::default());
+        let texts: Vec<(&str, usize, &str)> = found.iter()
+            .map(|h| (h.value.as_str(), h.line_number, h.call.as_str()))
+            .collect();
+
+        assert_eq!(texts, vec![
+            ("Hello, %{name}!", 3, "println!"),
+            ("Failed to open %{path}: %{err}", 6, "eprintln!"),
+            ("Settings", 9, "label()"),
+            ("Not found: %{arg0}", 14, "write!"),
+        ]);
+        assert_eq!(found[0].arguments, vec![("name".to_string(), "user.name".to_string())]);
+    }
+
+    #[test]
+    fn test_rewrite_content() {
+        let content = "//! Commands\n\nfn run(f: &mut Formatter) {\n    println!(\"Hello, {}!\", user.name);\n    write!(f, \"Done\")?;\n    ui.label(\"Settings\");\n}\n";
+        let mut found = scan_content(content, "src/main.rs", &ExtractOptions::default());
+        for (hardcoded, key) in found.iter_mut().zip(["hello", "done", "settings"]) {
+            hardcoded.suggested_key = key.to_string();
+        }
+        let items: Vec<&HardcodedString> = found.iter().collect();
+
+        assert_eq!(
+            rewrite_content(content, &items),
+            "//! Commands\n\nuse rust_i18n::t;\nfn run(f: &mut Formatter) {\n    println!(\"{}\", t!(\"hello\", name = user.name));\n    write!(f, \"{}\", t!(\"done\"))?;\n    ui.label(t!(\"settings\"));\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_format_string() {
+        let message = convert_format_string("{{{count}}} items, {:.2}%", &["count = items.len()", "ratio"]);
+        assert_eq!(message.value, "{%{count}} items, %{ratio}%");
+        assert_eq!(message.arguments, vec![
+            ("count".to_string(), "items.len()".to_string()),
+            ("ratio".to_string(), "ratio".to_string()),
+        ]);
+        assert!(message.has_format_spec);
+    }
+
+    #[test]
+    fn test_suggested_keys() {
+        assert_eq!(module_prefix(Path::new("commands/user.rs")), "commands.user");
+        assert_eq!(module_prefix(Path::new("main.rs")), "");
+        assert_eq!(key_slug("Failed to open %{path}: %{err}"), "failed_to_open");
+        assert_eq!(key_slug("404 Not Found"), "msg_404_not_found");
+        assert!(key_slug("设置").starts_with("msg_"));
+    }
+}
@@ -0,0 +1,227 @@
+//! 解析 rust-i18n 的 `i18n!()` 宏调用
+//!
+//! rust-i18n 在编译期以调用所在 crate 的 `CARGO_MANIFEST_DIR` 为基准解析翻译文件目录，
+//! 并支持 `fallback`、`minify_key` 等选项。工作区中的每个 crate 各自调用一次 `i18n!()`，
+//! 因此这里按 crate 分组，同一个 crate 中出现相互冲突的调用时报错。
+
+use anyhow::{Result, bail};
+use log::debug;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::syntax;
+
+/// 未指定路径时 rust-i18n 使用的翻译文件目录
+const DEFAULT_LOCALES_DIR: &str = "locales";
+
+/// 一次 `i18n!()` 宏调用
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct I18nInvocation {
+    /// 调用所在 crate 的目录（即 `CARGO_MANIFEST_DIR`）
+    pub crate_dir: PathBuf,
+    /// 调用所在的文件
+    pub file_path: PathBuf,
+    /// 调用所在的行号
+    pub line_number: usize,
+    /// 翻译文件目录参数，相对于 crate 目录
+    pub locales_dir: String,
+    /// `fallback` 选项中的语言列表
+    pub fallback: Vec<String>,
+    /// 其余选项，例如 `minify_key = true`；字符串字面量保存为去掉引号后的值
+    pub options: BTreeMap<String, String>,
+}
+
+impl I18nInvocation {
+    /// 翻译文件目录的完整路径
+    pub fn locales_path(&self) -> PathBuf {
+        self.crate_dir.join(&self.locales_dir)
+    }
+
+    /// 调用位置，用于错误信息
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.file_path.display(), self.line_number)
+    }
+
+    /// 两次调用的参数是否一致
+    fn same_arguments(&self, other: &I18nInvocation) -> bool {
+        self.locales_path() == other.locales_path()
+            && self.fallback == other.fallback
+            && self.options == other.options
+    }
+}
+
+/// `i18n!()` 宏的参数
+#[derive(Debug, Default, PartialEq, Eq)]
+struct I18nArgs {
+    locales_dir: Option<String>,
+    fallback: Vec<String>,
+    options: BTreeMap<String, String>,
+}
+
+/// 查找项目中所有 crate 的 `i18n!()` 调用，每个 crate 返回一个，按 crate 目录排序
+///
+/// 同一个 crate 中的多次调用参数必须一致，否则返回错误。
+pub fn find_invocations(project_path: &Path) -> Result<Vec<I18nInvocation>> {
+    let mut by_crate: BTreeMap<PathBuf, I18nInvocation> = BTreeMap::new();
+
+    for entry in WalkDir::new(project_path)
+        .follow_links(true)
+        .into_iter()
+        // 跳过构建产物和隐藏目录
+        .filter_entry(|e| {
+            e.depth() == 0 || !e.file_name().to_str().is_some_and(|name| name == "target" || name.starts_with('.'))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
+    {
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let crate_dir = crate_dir_of(entry.path(), project_path);
+
+        for invocation in parse_invocations(&content, entry.path(), &crate_dir)? {
+            debug!("检测到 i18n! 调用: {} ({})", invocation.location(), invocation.locales_dir);
+
+            match by_crate.get(&crate_dir) {
+                Some(existing) if !existing.same_arguments(&invocation) => bail!(
+                    "crate {} 中有相互冲突的 i18n! 调用: {} 和 {}",
+                    crate_dir.display(),
+                    existing.location(),
+                    invocation.location()
+                ),
+                Some(_) => {}
+                None => {
+                    by_crate.insert(crate_dir.clone(), invocation);
+                }
+            }
+        }
+    }
+
+    Ok(by_crate.into_values().collect())
+}
+
+/// 查找源代码目录所属 crate 的调用
+pub fn primary_invocation<'a>(invocations: &'a [I18nInvocation], src_path: &Path) -> Option<&'a I18nInvocation> {
+    let crate_dir = src_path.parent().map(normalize);
+    invocations.iter()
+        .find(|invocation| Some(normalize(&invocation.crate_dir)) == crate_dir)
+}
+
+/// 解析一个文件中的所有 `i18n!()` 调用，注释和字符串字面量中的调用除外
+fn parse_invocations(content: &str, file_path: &Path, crate_dir: &Path) -> Result<Vec<I18nInvocation>> {
+    let i18n_regex = Regex::new(r"(?:rust_i18n::)?\bi18n!\s*\(").unwrap();
+    let content = &syntax::code_only(content);
+    let mut invocations = Vec::new();
+
+    for m in i18n_regex.find_iter(content) {
+        let open = m.end() - 1;
+        let Some(close) = syntax::find_matching_delimiter(content, open) else {
+            continue;
+        };
+        let line_number = content[..m.start()].matches('\n').count() + 1;
+        let args = parse_args(&content[open + 1..close])
+            .map_err(|e| anyhow::anyhow!("{}:{}: {}", file_path.display(), line_number, e))?;
+
+        invocations.push(I18nInvocation {
+            crate_dir: crate_dir.to_path_buf(),
+            file_path: file_path.to_path_buf(),
+            line_number,
+            locales_dir: args.locales_dir.unwrap_or_else(|| DEFAULT_LOCALES_DIR.to_string()),
+            fallback: args.fallback,
+            options: args.options,
+        });
+    }
+
+    Ok(invocations)
+}
+
+/// 解析 `i18n!()` 的参数：可选的路径字面量，以及 `name = value` 形式的选项
+fn parse_args(args: &str) -> Result<I18nArgs> {
+    let mut result = I18nArgs::default();
+
+    for (index, part) in syntax::split_top_level(args, b',').into_iter().enumerate() {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+
+        let Some(eq) = syntax::find_at_depth_zero(part, 0, b'=') else {
+            // 只有第一个参数可以是不带名字的路径
+            match syntax::string_literals(part).as_slice() {
+                [path] if index == 0 && part.ends_with('"') => result.locales_dir = Some(path.clone()),
+                _ => bail!("无法解析 i18n! 参数: {}", part),
+            }
+            continue;
+        };
+
+        let name = part[..eq].trim();
+        let value = part[eq + 1..].trim();
+        if name == "fallback" {
+            result.fallback = syntax::string_literals(value);
+            if result.fallback.is_empty() {
+                bail!("无法解析 i18n! 的 fallback 参数: {}", value);
+            }
+        } else {
+            let value = match syntax::string_literals(value).as_slice() {
+                [literal] if value.starts_with('"') => literal.clone(),
+                _ => value.to_string(),
+            };
+            result.options.insert(name.to_string(), value);
+        }
+    }
+
+    Ok(result)
+}
+
+/// 查找文件所在 crate 的目录：向上查找最近的包含 Cargo.toml 的目录，最多到项目根目录
+fn crate_dir_of(file_path: &Path, project_path: &Path) -> PathBuf {
+    for dir in file_path.ancestors().skip(1) {
+        if dir.join("Cargo.toml").is_file() {
+            return dir.to_path_buf();
+        }
+        if dir == project_path {
+            break;
+        }
+    }
+    project_path.to_path_buf()
+}
+
+/// 尽量规范化路径，以便比较
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        let args = parse_args(r#""../locales", fallback = ["en", "zh-CN"], minify_key = true, minify_key_prefix = "t_","#).unwrap();
+        assert_eq!(args.locales_dir.as_deref(), Some("../locales"));
+        assert_eq!(args.fallback, vec!["en", "zh-CN"]);
+        assert_eq!(args.options.get("minify_key").map(String::as_str), Some("true"));
+        assert_eq!(args.options.get("minify_key_prefix").map(String::as_str), Some("t_"));
+
+        let args = parse_args(r#"fallback = "en""#).unwrap();
+        assert_eq!(args.locales_dir, None);
+        assert_eq!(args.fallback, vec!["en"]);
+
+        assert_eq!(parse_args("").unwrap(), I18nArgs::default());
+        assert!(parse_args(r#"fallback = "en", "locales""#).is_err());
+    }
+
+    #[test]
+    fn test_parse_invocations() {
+        let content = "// i18n!(\"ignored\");\n/* i18n!(\"ignored\") */\nlet s = r#\"i18n!(\"ignored\")\"#;\nrust_i18n::i18n!(\n    \"i18n\",\n    fallback = \"en\"\n);\n";
+        let invocations = parse_invocations(content, Path::new("app/src/lib.rs"), Path::new("app")).unwrap();
+
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].line_number, 4);
+        assert_eq!(invocations[0].locales_path(), PathBuf::from("app/i18n"));
+        assert_eq!(invocations[0].fallback, vec!["en"]);
+    }
+}
@@ -0,0 +1,60 @@
+//! i18n-audit：审计 rust-i18n 项目中的翻译键
+//!
+//! 除命令行工具外，也可以作为库在构建脚本或测试中调用。通过 [`AuditBuilder`] 配置审计，
+//! 构建时会按项目中的 `i18n!()` 调用确定翻译文件目录和默认语言：
+//!
+//! ```no_run
+//! use i18n_audit::AuditBuilder;
+//!
+//! let audit = AuditBuilder::new(env!("CARGO_MANIFEST_DIR")).build()?;
+//! let result = audit.run()?;
+//! assert!(result.missing_keys.is_empty(), "缺少翻译: {:?}", result.missing_keys);
+//! # Ok::<(), i18n_audit::Error>(())
+//! ```
+//!
+//! 也可以分别调用 [`Audit::scan_source_code`]、[`Audit::parse_translation_files`] 和 [`Audit::analyze`]，
+//! 只检查其中一部分结果。在构建脚本中使用 [`BuildCheck`]。
+
+pub mod analyzer;
+pub mod audit;
+pub mod build_script;
+pub mod config;
+pub mod error;
+pub mod i18n_macro;
+pub mod minify;
+pub mod parser;
+pub mod scanner;
+
+mod cfg;
+mod symbols;
+mod syntax;
+
+// 以下模块供命令行工具使用，不属于稳定的 API
+#[doc(hidden)]
+pub mod changes;
+#[doc(hidden)]
+pub mod extract;
+#[doc(hidden)]
+pub mod locale_writer;
+#[doc(hidden)]
+pub mod lsp;
+#[doc(hidden)]
+pub mod rename;
+#[doc(hidden)]
+pub mod report;
+#[doc(hidden)]
+pub mod spreadsheet;
+#[doc(hidden)]
+pub mod watch;
+#[doc(hidden)]
+pub mod workspace;
+#[doc(hidden)]
+pub mod xliff;
+
+pub use analyzer::{AnalysisResult, CallSite, DynamicKey, MissingKey, Severity, UnusedKey};
+pub use audit::{Audit, AuditBuilder};
+pub use build_script::BuildCheck;
+pub use config::Config;
+pub use error::Error;
+pub use parser::DefinedKey;
+pub use scanner::{UsageContext, UsedKey};
@@ -0,0 +1,1056 @@
+use anyhow::{Result, Context, bail};
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::parser::DefinedKey;
+
+/// 确定某个语言的翻译键应写入的文件
+///
+/// 优先使用该键在此语言中已有定义的文件，其次是该语言的任一翻译文件，
+/// 如果该语言还没有任何翻译文件，则参照默认语言文件的格式在翻译文件目录下新建 `{语言}.{扩展名}`。
+pub fn locate_target_file(
+    key: &str,
+    language: &str,
+    defined_keys: &[DefinedKey],
+    config: &Config,
+) -> PathBuf {
+    if let Some(def_key) = defined_keys.iter().find(|k| k.language == language && k.key == key) {
+        return config.project_path.join(&def_key.file_path);
+    }
+
+    // 同一语言中与该键共享最长前缀的文件，其次是以语言命名的文件
+    let prefix_len = |def_key: &DefinedKey| {
+        def_key.key.split('.')
+            .zip(key.split('.'))
+            .take_while(|(a, b)| a == b)
+            .count()
+    };
+    if let Some(def_key) = defined_keys.iter()
+        .filter(|k| k.language == language)
+        .max_by_key(|k| (prefix_len(k), is_named_after_language(&k.file_path, language)))
+    {
+        return config.project_path.join(&def_key.file_path);
+    }
+
+    let extension = defined_keys.iter()
+        .find(|k| k.language == config.default_locale)
+        .and_then(|k| Path::new(&k.file_path).extension())
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("yml")
+        .to_string();
+
+    config.locales_path().join(format!("{}.{}", language, extension))
+}
+
+/// 判断文件名是否就是语言代码
+fn is_named_after_language(file_path: &str, language: &str) -> bool {
+    Path::new(file_path).file_stem().and_then(|s| s.to_str()) == Some(language)
+}
+
+/// 要写入翻译文件的一个翻译
+#[derive(Debug, Clone)]
+pub struct KeyUpdate<'a> {
+    /// 目标翻译文件
+    pub path: PathBuf,
+    /// 语言代码
+    pub language: &'a str,
+    /// 翻译键
+    pub key: &'a str,
+    /// 翻译值
+    pub value: &'a str,
+}
+
+/// 在翻译文件中设置（新增或覆盖）一组翻译键，其他键保持不变
+///
+/// 先在内存中应用所有修改，全部成功后才写入文件，避免只写入一部分文件。
+pub fn set_keys(updates: &[KeyUpdate]) -> Result<()> {
+    // 按文件合并修改，保持文件首次出现的顺序
+    let mut contents: Vec<(&Path, String)> = Vec::new();
+    for update in updates {
+        debug!("写入翻译键 {} 到 {}", update.key, update.path.display());
+
+        let index = match contents.iter().position(|(path, _)| *path == update.path) {
+            Some(index) => index,
+            None => {
+                let content = if update.path.exists() {
+                    fs::read_to_string(&update.path)
+                        .with_context(|| format!("无法读取文件: {}", update.path.display()))?
+                } else {
+                    String::new()
+                };
+                contents.push((&update.path, content));
+                contents.len() - 1
+            }
+        };
+
+        let updated = with_key(&update.path, &contents[index].1, update.language, update.key, update.value)
+            .with_context(|| format!("无法写入翻译键 {} 到 {}", update.key, update.path.display()))?;
+        contents[index].1 = updated;
+    }
+
+    for (path, content) in contents {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("无法创建目录: {}", parent.display()))?;
+        }
+        fs::write(path, content)
+            .with_context(|| format!("无法写入文件: {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// 返回设置了翻译键之后的文件内容，按 `path` 的扩展名确定文件格式，不写入文件
+pub fn with_key(path: &Path, content: &str, language: &str, key: &str, value: &str) -> Result<String> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yml") | Some("yaml") => set_yaml_key(content, language, key, value),
+        Some("json") => set_json_key(content, language, key, value),
+        Some("toml") => set_toml_key(content, language, key, value),
+        Some("ftl") => set_fluent_key(content, key, value),
+        _ => bail!("不支持写入的文件类型: {}", path.display()),
+    }
+}
+
+/// 返回删除了翻译键之后的文件内容，删除后变空的上层映射一并删除
+pub fn without_key(path: &Path, content: &str, language: &str, key: &str) -> Result<String> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yml") | Some("yaml") => remove_yaml_key(content, language, key),
+        Some("json") => remove_json_key(content, language, key),
+        Some("toml") => remove_toml_key(content, language, key),
+        Some("ftl") => remove_fluent_key(content, key),
+        _ => bail!("不支持写入的文件类型: {}", path.display()),
+    }
+}
+
+/// 返回把翻译键 `old` 重命名为 `new` 之后的文件内容
+///
+/// 新键与旧键位于同一个上层映射时直接修改键名，键的位置、注释和格式保持不变；
+/// Fluent 文件直接修改消息 ID，保留消息的属性。其他情况删除旧键后以 `value` 写入新键。
+pub fn rename_key(path: &Path, content: &str, language: &str, old: &str, new: &str, value: &str) -> Result<String> {
+    let renamed = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ftl") => return rename_fluent_key(content, old, new),
+        Some("yml") | Some("yaml") => rename_yaml_key(content, language, old, new)?,
+        Some("json") => rename_json_key(content, language, old, new)?,
+        Some("toml") => rename_toml_key(content, language, old, new)?,
+        _ => None,
+    };
+    if let Some(renamed) = renamed {
+        return Ok(renamed);
+    }
+    let content = without_key(path, content, language, old)?;
+    with_key(path, &content, language, new, value)
+}
+
+/// 在上层映射中的键名：`relative` 是旧键的完整路径，`own_key` 是它在文件中写出的键名
+///
+/// 新键不在同一个上层映射中，或者需要新建一层映射时返回 None。
+fn renamed_own_key(relative: &str, own_key: &str, new: &str) -> Option<String> {
+    let parent = relative.strip_suffix(own_key)?.trim_end_matches('.');
+    let new_own_key = if parent.is_empty() {
+        new
+    } else {
+        new.strip_prefix(parent)?.strip_prefix('.')?
+    };
+    (!new_own_key.contains('.') || own_key.contains('.')).then(|| new_own_key.to_string())
+}
+
+/// YAML 文档中的一个映射项
+#[derive(Debug)]
+struct YamlEntry {
+    /// 从顶层到该项的键
+    path: Vec<String>,
+    /// 所在行
+    line: usize,
+    /// 缩进
+    indent: usize,
+    /// 冒号之后的位置
+    colon_end: usize,
+    /// 是否有行内的值（没有时为映射或空值）
+    has_value: bool,
+    /// 值占用的最后一行（块标量有续行）
+    value_end: usize,
+}
+
+/// 逐行读取块格式的 YAML 映射项，不支持的写法（列表、流式映射等）作为值跳过
+fn parse_yaml_entries(lines: &[String]) -> Vec<YamlEntry> {
+    let mut entries: Vec<YamlEntry> = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    // 正在读取的块标量（| 或 >）所属映射项的缩进
+    let mut block_indent: Option<usize> = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if let Some(block) = block_indent {
+            if trimmed.is_empty() || indent > block {
+                if !trimmed.is_empty() && let Some(entry) = entries.last_mut() {
+                    entry.value_end = index;
+                }
+                continue;
+            }
+            block_indent = None;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let Some((key, key_end)) = parse_yaml_key(trimmed) else {
+            continue;
+        };
+
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+        let mut path: Vec<String> = stack.iter().map(|(_, k)| k.clone()).collect();
+        path.push(key.clone());
+
+        let rest = trimmed[key_end..].trim();
+        if rest.starts_with('|') || rest.starts_with('>') {
+            block_indent = Some(indent);
+        }
+        stack.push((indent, key));
+        entries.push(YamlEntry {
+            path,
+            line: index,
+            indent,
+            colon_end: indent + key_end,
+            has_value: !rest.is_empty() && !rest.starts_with('#'),
+            value_end: index,
+        });
+    }
+
+    entries
+}
+
+/// 读取一行开头的映射键，返回键和冒号之后的位置
+fn parse_yaml_key(text: &str) -> Option<(String, usize)> {
+    let key_end = match text.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let mut chars = text.char_indices().skip(1);
+            loop {
+                let (i, c) = chars.next()?;
+                if c == '\\' && quote == '"' {
+                    chars.next();
+                } else if c == quote {
+                    break i + 1;
+                }
+            }
+        }
+        '-' | '[' | '{' | '#' | '&' | '*' | '!' | '|' | '>' | '%' | '@' | '`' => return None,
+        _ => text.find(": ").or_else(|| text.ends_with(':').then(|| text.len() - 1))?,
+    };
+
+    let after = text[key_end..].trim_start();
+    if !after.starts_with(':') || !(after.len() == 1 || after[1..].starts_with([' ', '\t'])) {
+        return None;
+    }
+    let key = serde_yaml::from_str::<String>(text[..key_end].trim_end()).ok()?;
+    Some((key, text.len() - after.len() + 1))
+}
+
+/// 把字符串写成 YAML 标量，多行字符串为块标量，续行缩进两个空格
+fn yaml_scalar(value: &str) -> Result<Vec<String>> {
+    let rendered = serde_yaml::to_string(&serde_yaml::Value::String(value.to_string()))?;
+    Ok(rendered.trim_end_matches('\n').lines().map(String::from).collect())
+}
+
+/// 顶层键是语言本身（`en:` 下再写翻译键）时返回这一项
+fn yaml_wrapper<'a>(entries: &'a [YamlEntry], language: &str) -> Option<&'a YamlEntry> {
+    let top_level: Vec<&YamlEntry> = entries.iter().filter(|e| e.path.len() == 1).collect();
+    match top_level.as_slice() {
+        [entry] if entry.path[0] == language && !entry.has_value && entries.len() > 1 => Some(*entry),
+        _ => None,
+    }
+}
+
+/// 映射项对应的翻译键，语言这一层不计入
+fn yaml_relative_key(entry: &YamlEntry, wrapper: Option<&YamlEntry>) -> Option<String> {
+    let base_len = usize::from(wrapper.is_some());
+    (entry.path.len() > base_len).then(|| entry.path[base_len..].join("."))
+}
+
+/// 映射项连同其下所有键占用的最后一行
+fn yaml_block_end(entries: &[YamlEntry], entry: &YamlEntry) -> usize {
+    entries.iter()
+        .filter(|e| e.path.starts_with(&entry.path))
+        .map(|e| e.value_end)
+        .max()
+        .unwrap_or(entry.value_end)
+}
+
+/// 在 YAML 文档中设置翻译键，只改动该键所在的行，保留注释、键的顺序和格式
+fn set_yaml_key(content: &str, language: &str, key: &str, value: &str) -> Result<String> {
+    if !content.trim().is_empty() {
+        serde_yaml::from_str::<serde_yaml::Value>(content).context("无法解析 YAML 内容")?;
+    }
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let entries = parse_yaml_entries(&lines);
+    let wrapper = yaml_wrapper(&entries, language);
+    let relative = |entry: &YamlEntry| yaml_relative_key(entry, wrapper);
+    let children = |parent: Option<&YamlEntry>| -> Vec<&YamlEntry> {
+        let depth = parent.map_or(0, |p| p.path.len());
+        entries.iter()
+            .filter(|e| e.path.len() == depth + 1 && parent.is_none_or(|p| e.path.starts_with(&p.path)))
+            .collect()
+    };
+    let block_end = |entry: &YamlEntry| yaml_block_end(&entries, entry);
+    let value_lines = yaml_scalar(value)?;
+
+    // 已有的键（嵌套或带点的完整路径）：只替换它的值
+    if let Some(entry) = entries.iter().find(|e| relative(e).as_deref() == Some(key)) {
+        if !entry.has_value && block_end(entry) > entry.line {
+            bail!("无法写入翻译键 {}: 该键下还有其他键", key);
+        }
+        let prefix = lines[entry.line][..entry.colon_end].to_string();
+        let mut replacement = vec![format!("{} {}", prefix, value_lines[0])];
+        replacement.extend(value_lines[1..].iter().map(|line| format!("{}{}", " ".repeat(entry.indent), line)));
+        lines.splice(entry.line..=entry.value_end, replacement);
+        return Ok(format!("{}\n", lines.join("\n")));
+    }
+
+    // 新的键：写在已存在的最深一层上层映射的末尾
+    let segments: Vec<&str> = key.split('.').collect();
+    let mut parent = wrapper;
+    let mut depth = 0;
+    for k in (1..segments.len()).rev() {
+        let prefix = segments[..k].join(".");
+        if let Some(entry) = entries.iter().find(|e| relative(e).as_deref() == Some(prefix.as_str())) {
+            if entry.has_value {
+                bail!("无法写入翻译键 {}: 路径上存在非映射的值", key);
+            }
+            parent = Some(entry);
+            depth = k;
+            break;
+        }
+    }
+
+    let step = entries.iter().map(|e| e.indent).filter(|&indent| indent > 0).min().unwrap_or(2);
+    let siblings = children(parent);
+    let indent = match (siblings.first(), parent) {
+        (Some(sibling), _) => sibling.indent,
+        (None, Some(parent)) => parent.indent + step,
+        (None, None) => 0,
+    };
+
+    // 同一层已经使用带点的完整路径作为键时沿用这种写法
+    let remaining: Vec<String> = if siblings.iter().any(|s| s.path.last().is_some_and(|k| k.contains('.'))) {
+        vec![segments[depth..].join(".")]
+    } else {
+        segments[depth..].iter().map(|s| s.to_string()).collect()
+    };
+
+    let mut new_lines = Vec::new();
+    for (i, segment) in remaining.iter().enumerate() {
+        let pad = " ".repeat(indent + i * step);
+        let segment = &yaml_scalar(segment)?[0];
+        if i + 1 < remaining.len() {
+            new_lines.push(format!("{}{}:", pad, segment));
+        } else {
+            new_lines.push(format!("{}{}: {}", pad, segment, value_lines[0]));
+            new_lines.extend(value_lines[1..].iter().map(|line| format!("{}{}", pad, line)));
+        }
+    }
+
+    let insert_at = match parent {
+        Some(parent) => block_end(parent) + 1,
+        None => entries.iter().map(|e| e.value_end + 1).max().unwrap_or(lines.len()),
+    };
+    lines.splice(insert_at..insert_at, new_lines);
+
+    Ok(format!("{}\n", lines.join("\n")))
+}
+
+/// JSON 文档中的一个对象及其成员的位置
+#[derive(Debug)]
+struct JsonObject {
+    /// 从顶层到该对象的键
+    path: Vec<String>,
+    /// `{` 的位置
+    open: usize,
+    /// `}` 的位置
+    close: usize,
+    members: Vec<JsonMember>,
+}
+
+/// JSON 对象的一个成员
+#[derive(Debug)]
+struct JsonMember {
+    key: String,
+    /// 键的范围（包括引号）
+    key_start: usize,
+    key_end: usize,
+    /// 值的范围
+    value_start: usize,
+    value_end: usize,
+    is_object: bool,
+}
+
+/// 记录 JSON 文档中各个对象位置的扫描器，假定文档已通过 serde_json 校验
+struct JsonScanner<'a> {
+    text: &'a str,
+    pos: usize,
+    objects: Vec<JsonObject>,
+}
+
+impl JsonScanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// 读取一个值；数组中的对象没有路径，不作为写入位置
+    fn value(&mut self, path: Option<Vec<String>>) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(path),
+            Some(b'[') => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b']') => break,
+                        Some(b',') => self.pos += 1,
+                        Some(_) => self.value(None)?,
+                        None => bail!("无法解析 JSON 内容: 数组未结束"),
+                    }
+                }
+                self.pos += 1;
+                Ok(())
+            }
+            Some(b'"') => self.string().map(|_| ()),
+            Some(_) => {
+                while self.peek().is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace()) {
+                    self.pos += 1;
+                }
+                Ok(())
+            }
+            None => bail!("无法解析 JSON 内容: 缺少值"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => break,
+                Some(_) => self.pos += 1,
+                None => bail!("无法解析 JSON 内容: 字符串未结束"),
+            }
+        }
+        self.pos += 1;
+        Ok(serde_json::from_str(&self.text[start..self.pos])?)
+    }
+
+    fn object(&mut self, path: Option<Vec<String>>) -> Result<()> {
+        let open = self.pos;
+        self.pos += 1;
+        let mut members = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') => break,
+                Some(b',') => {
+                    self.pos += 1;
+                    continue;
+                }
+                Some(b'"') => {}
+                _ => bail!("无法解析 JSON 内容: 对象中缺少键"),
+            }
+
+            let key_start = self.pos;
+            let key = self.string()?;
+            let key_end = self.pos;
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                bail!("无法解析 JSON 内容: 键 {} 之后缺少冒号", key);
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+
+            let value_start = self.pos;
+            let is_object = self.peek() == Some(b'{');
+            let child_path = path.as_ref().map(|p| {
+                let mut child = p.clone();
+                child.push(key.clone());
+                child
+            });
+            self.value(child_path)?;
+            members.push(JsonMember { key, key_start, key_end, value_start, value_end: self.pos, is_object });
+        }
+
+        let close = self.pos;
+        self.pos += 1;
+        if let Some(path) = path {
+            self.objects.push(JsonObject { path, open, close, members });
+        }
+        Ok(())
+    }
+}
+
+/// 位置所在行开头的缩进
+fn line_indent(text: &str, pos: usize) -> &str {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// 记录 JSON 文档中所有对象的位置
+fn scan_json_objects(content: &str) -> Result<Vec<JsonObject>> {
+    let mut scanner = JsonScanner { text: content, pos: 0, objects: Vec::new() };
+    scanner.value(Some(Vec::new()))?;
+    Ok(scanner.objects)
+}
+
+/// 顶层键是语言本身时为 1，否则为 0
+fn json_base_len(root: &JsonObject, language: &str) -> usize {
+    match root.members.as_slice() {
+        [member] if member.key == language && member.is_object => 1,
+        _ => 0,
+    }
+}
+
+/// 对象路径对应的翻译键前缀，语言这一层不计入
+fn json_relative_key(path: &[String], base_len: usize) -> Option<String> {
+    (path.len() >= base_len).then(|| path[base_len..].join("."))
+}
+
+/// 对象成员对应的翻译键
+fn json_member_key(object: &JsonObject, member: &JsonMember, base_len: usize) -> Option<String> {
+    match json_relative_key(&object.path, base_len) {
+        Some(prefix) if prefix.is_empty() => Some(member.key.clone()),
+        Some(prefix) => Some(format!("{}.{}", prefix, member.key)),
+        None => None,
+    }
+}
+
+/// 在 JSON 文档中设置翻译键，只改动该键的值或插入新的成员，保留键的顺序和格式
+fn set_json_key(content: &str, language: &str, key: &str, value: &str) -> Result<String> {
+    let content = if content.trim().is_empty() { "{}" } else { content };
+    serde_json::from_str::<serde_json::Value>(content).context("无法解析 JSON 内容")?;
+
+    let objects = scan_json_objects(content)?;
+    let Some(root) = objects.iter().find(|o| o.path.is_empty()) else {
+        bail!("无法写入翻译键 {}: JSON 顶层不是对象", key);
+    };
+    let base_len = json_base_len(root, language);
+    let relative = |path: &[String]| json_relative_key(path, base_len);
+    let joined = |object: &JsonObject, member: &JsonMember| json_member_key(object, member, base_len);
+    let quoted = serde_json::to_string(value)?;
+
+    // 已有的键（嵌套或带点的完整路径）：只替换它的值
+    for object in &objects {
+        for member in &object.members {
+            if joined(object, member).as_deref() == Some(key) {
+                if member.is_object {
+                    bail!("无法写入翻译键 {}: 该键下还有其他键", key);
+                }
+                return Ok(format!(
+                    "{}{}{}\n",
+                    &content[..member.value_start],
+                    quoted,
+                    content[member.value_end..].trim_end()
+                ));
+            }
+        }
+    }
+
+    // 新的键：写在已存在的最深一层上层对象的末尾
+    let segments: Vec<&str> = key.split('.').collect();
+    let mut target = None;
+    for k in (0..segments.len()).rev() {
+        let prefix = segments[..k].join(".");
+        let is_scalar = objects.iter()
+            .any(|o| o.members.iter().any(|m| !m.is_object && joined(o, m).as_deref() == Some(prefix.as_str())));
+        if is_scalar {
+            bail!("无法写入翻译键 {}: 路径上存在非对象的值", key);
+        }
+        if let Some(object) = objects.iter().find(|o| relative(&o.path).as_deref() == Some(prefix.as_str())) {
+            target = Some((object, k));
+            break;
+        }
+    }
+    let Some((object, depth)) = target else {
+        bail!("无法写入翻译键 {}: 找不到上层对象", key);
+    };
+
+    // 缩进单位取顶层成员的缩进，默认为两个空格
+    let unit = root.members.first()
+        .map(|m| line_indent(content, m.key_start).to_string())
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or_else(|| "  ".to_string());
+    let object_indent = line_indent(content, object.open);
+    let member_indent = match object.members.first() {
+        Some(member) => line_indent(content, member.key_start).to_string(),
+        None => format!("{}{}", object_indent, unit),
+    };
+
+    // 同一层已经使用带点的完整路径作为键时沿用这种写法
+    let remaining: Vec<String> = if object.members.iter().any(|m| m.key.contains('.')) {
+        vec![segments[depth..].join(".")]
+    } else {
+        segments[depth..].iter().map(|s| s.to_string()).collect()
+    };
+
+    fn member_text(segments: &[String], value: &str, indent: &str, unit: &str) -> Result<String> {
+        let key = serde_json::to_string(&segments[0])?;
+        if segments.len() == 1 {
+            return Ok(format!("{}: {}", key, value));
+        }
+        let inner_indent = format!("{}{}", indent, unit);
+        let inner = member_text(&segments[1..], value, &inner_indent, unit)?;
+        Ok(format!("{}: {{\n{}{}\n{}}}", key, inner_indent, inner, indent))
+    }
+    let text = member_text(&remaining, &quoted, &member_indent, &unit)?;
+
+    let updated = match object.members.last() {
+        Some(last) => format!(
+            "{},\n{}{}{}",
+            &content[..last.value_end],
+            member_indent,
+            text,
+            &content[last.value_end..]
+        ),
+        None => format!(
+            "{}\n{}{}\n{}{}",
+            &content[..object.open + 1],
+            member_indent,
+            text,
+            object_indent,
+            &content[object.close..]
+        ),
+    };
+
+    Ok(format!("{}\n", updated.trim_end()))
+}
+
+/// 在 TOML 文档中设置翻译键，保留注释、键的顺序和格式
+fn set_toml_key(content: &str, language: &str, key: &str, value: &str) -> Result<String> {
+    let mut document: toml_edit::DocumentMut = content.parse().context("无法解析 TOML 内容")?;
+
+    let mut item = document.as_item_mut();
+
+    // 如果顶层键是语言本身，则从下一层开始
+    let is_wrapped = item.as_table_like()
+        .is_some_and(|table| table.len() == 1 && table.get(language).is_some_and(|v| v.is_table_like()));
+    if is_wrapped {
+        item = &mut item[language];
+    }
+
+    let segments: Vec<&str> = key.split('.').collect();
+    for (i, segment) in segments.iter().enumerate() {
+        let is_inline = item.is_inline_table();
+        let Some(table) = item.as_table_like_mut() else {
+            bail!("无法写入翻译键 {}: 路径上存在非表的值", key);
+        };
+
+        // 也支持直接以带点的完整路径作为键的写法
+        let rest = segments[i..].join(".");
+        if i == segments.len() - 1 || table.contains_key(&rest) {
+            match table.get_mut(&rest) {
+                Some(existing) => {
+                    let Some(existing) = existing.as_value_mut() else {
+                        bail!("无法写入翻译键 {}: 该键下还有其他键", key);
+                    };
+                    let decor = existing.decor().clone();
+                    *existing = toml_edit::Value::from(value);
+                    *existing.decor_mut() = decor;
+                }
+                None => {
+                    table.insert(&rest, toml_edit::value(value));
+                }
+            }
+            break;
+        }
+
+        if !table.contains_key(segment) {
+            let child = if is_inline {
+                toml_edit::Item::Value(toml_edit::Value::InlineTable(toml_edit::InlineTable::new()))
+            } else {
+                let mut child = toml_edit::Table::new();
+                child.set_implicit(true);
+                toml_edit::Item::Table(child)
+            };
+            table.insert(segment, child);
+        }
+        item = table.get_mut(segment).unwrap();
+    }
+
+    Ok(document.to_string())
+}
+
+/// 从 YAML 文档中删除翻译键，只删除该键所在的行，删除后变空的上层映射一并删除
+fn remove_yaml_key(content: &str, language: &str, key: &str) -> Result<String> {
+    serde_yaml::from_str::<serde_yaml::Value>(content).context("无法解析 YAML 内容")?;
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let entries = parse_yaml_entries(&lines);
+    let wrapper = yaml_wrapper(&entries, language);
+    let Some(mut removed) = entries.iter().find(|e| yaml_relative_key(e, wrapper).as_deref() == Some(key)) else {
+        return Ok(content.to_string());
+    };
+
+    // 上层映射中只有这一个键时，连同上层映射一起删除
+    while let Some(parent) = entries.iter()
+        .find(|e| e.path.len() + 1 == removed.path.len() && removed.path.starts_with(&e.path))
+        .filter(|parent| !wrapper.is_some_and(|w| std::ptr::eq(w, *parent)))
+    {
+        let has_siblings = entries.iter()
+            .any(|e| e.path.len() == removed.path.len() && e.path.starts_with(&parent.path) && e.path != removed.path);
+        if has_siblings {
+            break;
+        }
+        removed = parent;
+    }
+
+    lines.drain(removed.line..=yaml_block_end(&entries, removed));
+    Ok(format!("{}\n", lines.join("\n")))
+}
+
+/// 在 YAML 文档中修改键名，只改动该键所在的行；新键不在同一个上层映射中时返回 None
+fn rename_yaml_key(content: &str, language: &str, old: &str, new: &str) -> Result<Option<String>> {
+    serde_yaml::from_str::<serde_yaml::Value>(content).context("无法解析 YAML 内容")?;
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let entries = parse_yaml_entries(&lines);
+    let wrapper = yaml_wrapper(&entries, language);
+    let Some(entry) = entries.iter().find(|e| yaml_relative_key(e, wrapper).as_deref() == Some(old)) else {
+        return Ok(None);
+    };
+    let Some(new_key) = renamed_own_key(old, entry.path.last().unwrap(), new) else {
+        return Ok(None);
+    };
+
+    let line = &lines[entry.line];
+    let renamed = format!("{}{}{}", &line[..entry.indent], yaml_scalar(&new_key)?[0], &line[entry.colon_end - 1..]);
+    lines[entry.line] = renamed;
+    Ok(Some(format!("{}\n", lines.join("\n"))))
+}
+
+/// 从 JSON 文档中删除翻译键，只删除该成员，删除后变空的上层对象一并删除
+fn remove_json_key(content: &str, language: &str, key: &str) -> Result<String> {
+    serde_json::from_str::<serde_json::Value>(content).context("无法解析 JSON 内容")?;
+
+    let objects = scan_json_objects(content)?;
+    let Some(root) = objects.iter().find(|o| o.path.is_empty()) else {
+        return Ok(content.to_string());
+    };
+    let base_len = json_base_len(root, language);
+    let Some(mut target) = objects.iter()
+        .flat_map(|o| o.members.iter().map(move |m| (o, m)))
+        .find(|(o, m)| json_member_key(o, m, base_len).as_deref() == Some(key))
+    else {
+        return Ok(content.to_string());
+    };
+
+    // 上层对象中只有这一个成员时，连同上层对象一起删除
+    loop {
+        let (object, member) = target;
+        let parent = (object.members.len() == 1 && object.path.len() > base_len)
+            .then(|| objects.iter().find(|o| o.path.len() + 1 == object.path.len() && object.path.starts_with(&o.path)))
+            .flatten()
+            .and_then(|parent| parent.members.iter().find(|m| Some(&m.key) == object.path.last()).map(|m| (parent, m)));
+        match parent {
+            Some(parent) => target = parent,
+            None => {
+                let index = object.members.iter().position(|m| std::ptr::eq(m, member)).unwrap();
+                // 连同相邻的逗号一起删除：不是最后一个成员时删到下一个成员之前，否则从上一个成员之后删起
+                let range = match (index.checked_sub(1), object.members.get(index + 1)) {
+                    (_, Some(next)) => member.key_start..next.key_start,
+                    (Some(previous), None) => object.members[previous].value_end..member.value_end,
+                    (None, None) => object.open + 1..object.close,
+                };
+                return Ok(format!("{}{}\n", &content[..range.start], content[range.end..].trim_end()));
+            }
+        }
+    }
+}
+
+/// 在 JSON 文档中修改键名，只改动该成员的键；新键不在同一个上层对象中时返回 None
+fn rename_json_key(content: &str, language: &str, old: &str, new: &str) -> Result<Option<String>> {
+    serde_json::from_str::<serde_json::Value>(content).context("无法解析 JSON 内容")?;
+
+    let objects = scan_json_objects(content)?;
+    let Some(root) = objects.iter().find(|o| o.path.is_empty()) else {
+        return Ok(None);
+    };
+    let base_len = json_base_len(root, language);
+    let Some(member) = objects.iter()
+        .flat_map(|o| o.members.iter().map(move |m| (o, m)))
+        .find(|(o, m)| json_member_key(o, m, base_len).as_deref() == Some(old))
+        .map(|(_, m)| m)
+    else {
+        return Ok(None);
+    };
+    let Some(new_key) = renamed_own_key(old, &member.key, new) else {
+        return Ok(None);
+    };
+
+    Ok(Some(format!(
+        "{}{}{}",
+        &content[..member.key_start],
+        serde_json::to_string(&new_key)?,
+        &content[member.key_end..]
+    )))
+}
+
+/// 从 TOML 文档中删除翻译键，保留注释和格式，删除后变空的上层表一并删除
+fn remove_toml_key(content: &str, language: &str, key: &str) -> Result<String> {
+    let mut document: toml_edit::DocumentMut = content.parse().context("无法解析 TOML 内容")?;
+
+    let mut item = document.as_item_mut();
+    let is_wrapped = item.as_table_like()
+        .is_some_and(|table| table.len() == 1 && table.get(language).is_some_and(|v| v.is_table_like()));
+    if is_wrapped {
+        item = &mut item[language];
+    }
+
+    fn remove(item: &mut toml_edit::Item, segments: &[&str]) -> bool {
+        let Some(table) = item.as_table_like_mut() else {
+            return false;
+        };
+        // 也支持直接以带点的完整路径作为键的写法
+        if table.remove(&segments.join(".")).is_none()
+            && segments.len() > 1
+            && table.get_mut(segments[0]).is_some_and(|child| remove(child, &segments[1..]))
+        {
+            table.remove(segments[0]);
+        }
+        table.is_empty()
+    }
+    remove(item, &key.split('.').collect::<Vec<_>>());
+
+    Ok(document.to_string())
+}
+
+/// 在 TOML 文档中修改键名，保留键的位置、注释和格式；新键不在同一个表中时返回 None
+fn rename_toml_key(content: &str, language: &str, old: &str, new: &str) -> Result<Option<String>> {
+    let mut document: toml_edit::DocumentMut = content.parse().context("无法解析 TOML 内容")?;
+
+    let mut item = document.as_item_mut();
+    let is_wrapped = item.as_table_like()
+        .is_some_and(|table| table.len() == 1 && table.get(language).is_some_and(|v| v.is_table_like()));
+    if is_wrapped {
+        item = &mut item[language];
+    }
+
+    // 找到直接包含该键的表，也支持直接以带点的完整路径作为键的写法
+    let segments: Vec<&str> = old.split('.').collect();
+    let mut own_key = None;
+    for i in 0..segments.len() {
+        let rest = segments[i..].join(".");
+        let Some(table) = item.as_table_like() else {
+            return Ok(None);
+        };
+        if table.get(&rest).is_some_and(|v| v.is_value()) {
+            own_key = Some(rest);
+            break;
+        }
+        if i + 1 == segments.len() || !table.contains_key(segments[i]) {
+            return Ok(None);
+        }
+        item = &mut item[segments[i]];
+    }
+    let Some(own_key) = own_key else {
+        return Ok(None);
+    };
+    let (Some(new_key), Some(table)) = (renamed_own_key(old, &own_key, new), item.as_table_mut()) else {
+        return Ok(None);
+    };
+
+    // 在原来的位置插入新键，沿用旧键前面的注释
+    let order: Vec<String> = table.iter().map(|(k, _)| k.to_string()).collect();
+    let decor = table.key(&own_key).map(|k| k.leaf_decor().clone());
+    let value = table.remove(&own_key).unwrap();
+    table.insert(&new_key, value);
+    if let (Some(decor), Some(mut key)) = (decor, table.key_mut(&new_key)) {
+        *key.leaf_decor_mut() = decor;
+    }
+    let position = |key: &str| order.iter().position(|k| k == if key == new_key { own_key.as_str() } else { key });
+    table.sort_values_by(|a, _, b, _| match (position(a.get()), position(b.get())) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => std::cmp::Ordering::Equal,
+    });
+
+    Ok(Some(document.to_string()))
+}
+
+/// 判断 Fluent 文档中的某一行是否是指定消息的开头
+fn is_fluent_message(line: &str, key: &str) -> bool {
+    line.split_once('=')
+        .map(|(id, _)| !line.starts_with([' ', '\t']) && id.trim() == key)
+        .unwrap_or(false)
+}
+
+/// 从 Fluent 文档中删除消息及其续行和属性
+fn remove_fluent_key(content: &str, key: &str) -> Result<String> {
+    if key.contains('.') {
+        bail!("暂不支持删除 Fluent 属性: {}", key);
+    }
+
+    let mut output: Vec<&str> = Vec::new();
+    let mut lines = content.lines().peekable();
+    while let Some(line) = lines.next() {
+        if !is_fluent_message(line, key) {
+            output.push(line);
+            continue;
+        }
+        while lines.peek().is_some_and(|next| next.starts_with([' ', '\t'])) {
+            lines.next();
+        }
+    }
+
+    Ok(format!("{}\n", output.join("\n")))
+}
+
+/// 修改 Fluent 文档中的消息 ID
+fn rename_fluent_key(content: &str, old: &str, new: &str) -> Result<String> {
+    if old.contains('.') || new.contains('.') {
+        bail!("暂不支持重命名 Fluent 属性: {}", old);
+    }
+
+    let output: Vec<String> = content.lines()
+        .map(|line| match line.split_once('=') {
+            Some((_, rest)) if is_fluent_message(line, old) => format!("{} ={}", new, rest),
+            _ => line.to_string(),
+        })
+        .collect();
+
+    Ok(format!("{}\n", output.join("\n")))
+}
+
+/// 在 Fluent 文档中设置消息的值，保留消息的属性和文件中的其他内容
+fn set_fluent_key(content: &str, key: &str, value: &str) -> Result<String> {
+    if key.contains('.') {
+        bail!("暂不支持写入 Fluent 属性: {}", key);
+    }
+
+    let new_entry: Vec<String> = {
+        let mut lines = value.lines();
+        let first = lines.next().unwrap_or_default();
+        std::iter::once(format!("{} = {}", key, first))
+            .chain(lines.map(|line| format!("    {}", line)))
+            .collect()
+    };
+
+    let mut output: Vec<String> = Vec::new();
+    let mut replaced = false;
+    let mut lines = content.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if !is_fluent_message(line, key) {
+            output.push(line.to_string());
+            continue;
+        }
+
+        // 跳过原有值的续行，保留属性
+        while let Some(next) = lines.peek() {
+            if next.starts_with([' ', '\t']) && !next.trim_start().starts_with('.') {
+                lines.next();
+            } else {
+                break;
+            }
+        }
+        output.extend(new_entry.iter().cloned());
+        replaced = true;
+    }
+
+    if !replaced {
+        output.extend(new_entry);
+    }
+
+    Ok(format!("{}\n", output.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_yaml_key_in_place() {
+        let content = "# 用户相关\nuser:\n  name: Name\n  age: Age\n\n# 菜单\nmenu.file: File\n";
+
+        let updated = set_yaml_key(content, "en", "user.name", "Full name").unwrap();
+        let updated = set_yaml_key(&updated, "en", "user.email", "Email").unwrap();
+        let updated = set_yaml_key(&updated, "en", "menu.file", "Open file").unwrap();
+        let updated = set_yaml_key(&updated, "en", "menu.edit", "Edit").unwrap();
+        assert_eq!(
+            updated,
+            "# 用户相关\nuser:\n  name: Full name\n  age: Age\n  email: Email\n\n# 菜单\nmenu.file: Open file\nmenu.edit: Edit\n"
+        );
+
+        let nested = set_yaml_key("", "en", "user.profile.bio", "Line 1\nLine 2").unwrap();
+        assert_eq!(nested, "user:\n  profile:\n    bio: |-\n      Line 1\n      Line 2\n");
+        assert!(set_yaml_key(content, "en", "user", "User").is_err());
+
+        let wrapped = set_yaml_key("en:\n  hello: Hello\n", "en", "goodbye", "Goodbye").unwrap();
+        assert_eq!(wrapped, "en:\n  hello: Hello\n  goodbye: Goodbye\n");
+    }
+
+    #[test]
+    fn test_set_json_key_in_place() {
+        let content = "{\n    \"user\": {\n        \"name\": \"Name\"\n    },\n    \"menu.file\": \"File\"\n}\n";
+
+        let updated = set_json_key(content, "en", "user.name", "Full \"name\"").unwrap();
+        let updated = set_json_key(&updated, "en", "user.email", "Email").unwrap();
+        let updated = set_json_key(&updated, "en", "menu.file", "Open file").unwrap();
+        let updated = set_json_key(&updated, "en", "menu.edit", "Edit").unwrap();
+        assert_eq!(
+            updated,
+            "{\n    \"user\": {\n        \"name\": \"Full \\\"name\\\"\",\n        \"email\": \"Email\"\n    },\n    \"menu.file\": \"Open file\",\n    \"menu.edit\": \"Edit\"\n}\n"
+        );
+
+        let nested = set_json_key("{}", "en", "user.name", "Name").unwrap();
+        assert_eq!(nested, "{\n  \"user\": {\n    \"name\": \"Name\"\n  }\n}\n");
+
+        let wrapped = set_json_key("{\"en\": {\"hello\": \"Hello\"}}", "en", "hello", "Hi").unwrap();
+        assert_eq!(wrapped, "{\"en\": {\"hello\": \"Hi\"}}\n");
+    }
+
+    #[test]
+    fn test_rename_key_in_place() {
+        let yaml = "# 用户相关\nuser:\n  # 显示名\n  name: Name\n  age: Age # 年龄\n\n# 菜单\nmenu:\n  file: File\n";
+        let renamed = rename_key(Path::new("en.yml"), yaml, "en", "user.name", "user.full_name", "Name").unwrap();
+        assert_eq!(renamed, yaml.replace("  name: Name", "  full_name: Name"));
+        let moved = rename_key(Path::new("en.yml"), yaml, "en", "menu.file", "user.file", "File").unwrap();
+        assert_eq!(moved, "# 用户相关\nuser:\n  # 显示名\n  name: Name\n  age: Age # 年龄\n  file: File\n\n# 菜单\n");
+
+        let json = "{\n    \"user\": {\n        \"name\": \"Name\",\n        \"age\": \"Age\"\n    },\n    \"menu\": {\"file\": \"File\"}\n}\n";
+        let renamed = rename_key(Path::new("en.json"), json, "en", "user.name", "user.full_name", "Name").unwrap();
+        assert_eq!(renamed, json.replace("\"name\"", "\"full_name\""));
+        let moved = rename_key(Path::new("en.json"), json, "en", "menu.file", "user.file", "File").unwrap();
+        assert_eq!(
+            moved,
+            "{\n    \"user\": {\n        \"name\": \"Name\",\n        \"age\": \"Age\",\n        \"file\": \"File\"\n    }\n}\n"
+        );
+
+        let toml = "# 标题\ntitle = \"Title\"\n\n[user]\n# 显示名\nname = \"Name\" # 全名\nage = \"Age\"\n\n[menu]\nfile = \"File\"\n";
+        let renamed = rename_key(Path::new("en.toml"), toml, "en", "user.name", "user.full_name", "Name").unwrap();
+        assert_eq!(renamed, toml.replace("\nname = ", "\nfull_name = "));
+        let removed = without_key(Path::new("en.toml"), toml, "en", "menu.file").unwrap();
+        assert_eq!(removed, "# 标题\ntitle = \"Title\"\n\n[user]\n# 显示名\nname = \"Name\" # 全名\nage = \"Age\"\n");
+    }
+
+    #[test]
+    fn test_set_toml_key_in_place() {
+        let content = "# 标题\ntitle = \"Title\"\n\"menu.file\" = \"File\"\n\n[user]\nname = \"Name\" # 显示名\n";
+
+        let updated = set_toml_key(content, "en", "user.name", "Full name").unwrap();
+        let updated = set_toml_key(&updated, "en", "user.email", "Email").unwrap();
+        let updated = set_toml_key(&updated, "en", "menu.file", "Open file").unwrap();
+        assert_eq!(
+            updated,
+            "# 标题\ntitle = \"Title\"\n\"menu.file\" = \"Open file\"\n\n[user]\nname = \"Full name\" # 显示名\nemail = \"Email\"\n"
+        );
+    }
+}
@@ -0,0 +1,602 @@
+//! 语言服务器：在编辑器中显示翻译键的诊断、悬停提示、跳转和补全
+//!
+//! 通过标准输入输出使用 Language Server Protocol 通信，复用扫描、解析和分析流程。
+//! 源文件修改时只重新扫描该文件（包括未保存的内容），翻译文件保存时重新解析翻译文件，
+//! 然后重新分析并发布诊断。
+
+use anyhow::Result;
+use log::{info, warn};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    CompletionTextEdit, Diagnostic, DiagnosticSeverity, DiagnosticTag, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability, Location,
+    MarkupContent, MarkupKind, OneOf, Position, PublishDiagnosticsParams, Range, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, TextEdit, Uri,
+};
+use regex::Regex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::{AnalysisResult, Severity};
+use crate::config::Config;
+use crate::workspace::Workspace;
+
+/// 诊断信息的来源名称
+const DIAGNOSTIC_SOURCE: &str = "i18n-audit";
+
+/// 通过标准输入输出运行语言服务器，直到编辑器发出退出通知
+pub fn run(config: Config) -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = serde_json::to_value(server_capabilities())?;
+    connection.initialize(capabilities)?;
+    info!("语言服务器已初始化");
+
+    let mut server = Server::load(config)?;
+    server.publish_diagnostics(&connection)?;
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let response = server.handle_request(request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                match server.handle_notification(notification) {
+                    Ok(true) => server.publish_diagnostics(&connection)?,
+                    Ok(false) => {}
+                    Err(e) => warn!("处理通知失败: {:#}", e),
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    // 关闭连接后写线程才会结束
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// 语言服务器支持的功能
+fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+            open_close: Some(true),
+            change: Some(TextDocumentSyncKind::FULL),
+            save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+            ..Default::default()
+        })),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["\"".to_string(), ".".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// 语言服务器的状态
+struct Server {
+    workspace: Workspace,
+    result: AnalysisResult,
+    /// 编辑器中打开的文件的当前内容，可能尚未保存
+    documents: HashMap<PathBuf, String>,
+    /// 上次发布了诊断的文件，重新分析后需要清除已经消失的诊断
+    published: HashSet<PathBuf>,
+}
+
+impl Server {
+    /// 扫描整个项目并完成第一次分析
+    fn load(config: Config) -> Result<Self> {
+        let workspace = Workspace::load(config)?;
+        let result = workspace.analyze()?;
+
+        Ok(Server {
+            workspace,
+            result,
+            documents: HashMap::new(),
+            published: HashSet::new(),
+        })
+    }
+
+    /// 文件的当前内容：优先使用编辑器中未保存的内容
+    fn content(&self, path: &Path) -> String {
+        match self.documents.get(path) {
+            Some(content) => content.clone(),
+            None => fs::read_to_string(path).unwrap_or_default(),
+        }
+    }
+
+    /// 按文件的当前内容重新扫描
+    fn rescan(&mut self, path: &Path) -> Result<bool> {
+        let content = self.content(path);
+        self.workspace.rescan(path, &content)
+    }
+
+    /// 处理文件打开、修改、保存和关闭通知；返回是否需要重新发布诊断
+    fn handle_notification(&mut self, notification: Notification) -> Result<bool> {
+        let changed = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = extract_notification::<DidOpenTextDocument>(notification)?;
+                let Some(path) = uri_to_path(&params.text_document.uri) else {
+                    return Ok(false);
+                };
+                self.documents.insert(path.clone(), params.text_document.text);
+                self.rescan(&path)?
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = extract_notification::<DidChangeTextDocument>(notification)?;
+                let Some(path) = uri_to_path(&params.text_document.uri) else {
+                    return Ok(false);
+                };
+                // 同步方式为全量同步，最后一次修改即为完整内容
+                let Some(change) = params.content_changes.into_iter().last() else {
+                    return Ok(false);
+                };
+                self.documents.insert(path.clone(), change.text);
+                self.rescan(&path)?
+            }
+            DidSaveTextDocument::METHOD => {
+                let params = extract_notification::<DidSaveTextDocument>(notification)?;
+                let Some(path) = uri_to_path(&params.text_document.uri) else {
+                    return Ok(false);
+                };
+                // 翻译文件从磁盘解析，保存后才重新解析
+                if self.workspace.is_locale_file(&path) {
+                    info!("翻译文件已保存，重新解析: {}", path.display());
+                    self.workspace.reload_translations()?;
+                    true
+                } else {
+                    false
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = extract_notification::<DidCloseTextDocument>(notification)?;
+                let Some(path) = uri_to_path(&params.text_document.uri) else {
+                    return Ok(false);
+                };
+                // 放弃未保存的修改，按磁盘上的内容重新扫描
+                self.documents.remove(&path);
+                self.rescan(&path)?
+            }
+            _ => false,
+        };
+
+        if changed {
+            self.result = self.workspace.analyze()?;
+        }
+        Ok(changed)
+    }
+
+    /// 处理悬停、跳转到定义和补全请求
+    fn handle_request(&self, request: Request) -> Response {
+        match request.method.as_str() {
+            HoverRequest::METHOD => respond::<HoverRequest>(request, |params| self.hover(params)),
+            GotoDefinition::METHOD => respond::<GotoDefinition>(request, |params| self.definition(params)),
+            Completion::METHOD => respond::<Completion>(request, |params| self.completion(params)),
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("不支持的请求: {}", request.method),
+            ),
+        }
+    }
+
+    /// 光标所在位置的字符串字面量引用的翻译键，以及该字面量的范围
+    fn key_at(&self, uri: &Uri, position: Position) -> Option<(String, Range)> {
+        let path = uri_to_path(uri)?;
+        let content = self.content(&path);
+        let line = content.lines().nth(position.line as usize)?;
+        let (start, end, literal) = string_literal_at(line, utf16_to_byte(line, position.character))?;
+
+        // 启用 minify_key 时，t!() 中写的是消息原文
+        let key = self.workspace.config.minify_key.key_for(&literal);
+        let is_known = self.workspace.defined_keys.iter().any(|k| k.key == key)
+            || self.workspace.used_keys().any(|u| u.key == key);
+        if !is_known {
+            return None;
+        }
+
+        let range = Range::new(
+            Position::new(position.line, byte_to_utf16(line, start)),
+            Position::new(position.line, byte_to_utf16(line, end)),
+        );
+        Some((key, range))
+    }
+
+    /// 悬停提示：列出翻译键在每种语言中的值
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let (key, range) = self.key_at(&position.text_document.uri, position.position)?;
+
+        let languages: BTreeSet<&str> = self.workspace.defined_keys.iter().map(|k| k.language.as_str()).collect();
+        let mut languages: Vec<&str> = languages.into_iter().collect();
+        // 默认语言排在最前面
+        languages.sort_by_key(|language| *language != self.workspace.config.default_locale);
+
+        let mut markdown = format!("**{}**\n\n| 语言 | 翻译 |\n| --- | --- |\n", key);
+        for language in languages {
+            let value = self.workspace.defined_keys.iter()
+                .find(|k| k.key == key && k.language == language)
+                .map(|k| k.value.replace('|', "\\|").replace('\n', " "))
+                .unwrap_or_else(|| "*(缺失)*".to_string());
+            markdown.push_str(&format!("| {} | {} |\n", language, value));
+        }
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: markdown,
+            }),
+            range: Some(range),
+        })
+    }
+
+    /// 跳转到定义：翻译键在每个翻译文件中的定义位置
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let position = params.text_document_position_params;
+        let (key, _) = self.key_at(&position.text_document.uri, position.position)?;
+
+        let locations: Vec<Location> = self.workspace.defined_keys.iter()
+            .filter(|k| k.key == key)
+            .filter_map(|k| {
+                let path = self.workspace.config.project_path.join(&k.file_path);
+                let range = key_range(&self.content(&path), &k.key).unwrap_or_default();
+                Some(Location::new(path_to_uri(&path)?, range))
+            })
+            .collect();
+
+        (!locations.is_empty()).then_some(GotoDefinitionResponse::Array(locations))
+    }
+
+    /// 在 `t!("` 之后补全已定义的翻译键
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let position = params.text_document_position;
+        let path = uri_to_path(&position.text_document.uri)?;
+        let content = self.content(&path);
+        let line = content.lines().nth(position.position.line as usize).unwrap_or_default();
+        let before_cursor = &line[..utf16_to_byte(line, position.position.character)];
+        let typed = completion_prefix(before_cursor)?;
+
+        // 替换已经输入的部分，键中的 `.` 不会打断补全
+        let range = Range::new(
+            Position::new(position.position.line, position.position.character - utf16_len(typed)),
+            position.position,
+        );
+
+        let mut keys: BTreeMap<&str, Option<&str>> = BTreeMap::new();
+        for def_key in &self.workspace.defined_keys {
+            let value = keys.entry(def_key.key.as_str()).or_default();
+            if def_key.language == self.workspace.config.default_locale {
+                *value = Some(def_key.value.as_str());
+            }
+        }
+
+        let items = keys.into_iter()
+            .map(|(key, value)| CompletionItem {
+                label: key.to_string(),
+                kind: Some(CompletionItemKind::CONSTANT),
+                detail: value.map(str::to_string),
+                text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(range, key.to_string()))),
+                ..Default::default()
+            })
+            .collect();
+        Some(CompletionResponse::Array(items))
+    }
+
+    /// 按文件收集诊断：调用处缺少的翻译，以及翻译文件中未使用的键
+    fn diagnostics(&self) -> HashMap<PathBuf, Vec<Diagnostic>> {
+        let mut diagnostics: HashMap<PathBuf, Vec<Diagnostic>> = HashMap::new();
+
+        for missing in &self.result.missing_keys {
+            let severity = match missing.severity {
+                Severity::High => DiagnosticSeverity::ERROR,
+                Severity::Normal => DiagnosticSeverity::WARNING,
+            };
+            let message = format!(
+                "翻译键 {} 缺少以下语言的翻译: {}",
+                missing.message.as_deref().unwrap_or(&missing.key),
+                missing.missing_languages.join(", ")
+            );
+
+            for site in &missing.call_sites {
+                let path = self.workspace.config.project_path.join(&site.file_path);
+                let content = self.content(&path);
+                let line = content.lines().nth(site.line_number.saturating_sub(1)).unwrap_or_default();
+                let (start, end) = call_span(line, site.column);
+                let line_index = site.line_number.saturating_sub(1) as u32;
+
+                diagnostics.entry(path).or_default().push(Diagnostic {
+                    range: Range::new(
+                        Position::new(line_index, byte_to_utf16(line, start)),
+                        Position::new(line_index, byte_to_utf16(line, end)),
+                    ),
+                    severity: Some(severity),
+                    source: Some(DIAGNOSTIC_SOURCE.to_string()),
+                    message: message.clone(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        for unused in self.result.unused_keys.values().flatten() {
+            let path = self.workspace.config.project_path.join(&unused.file_path);
+            let range = key_range(&self.content(&path), &unused.key).unwrap_or_default();
+
+            diagnostics.entry(path).or_default().push(Diagnostic {
+                range,
+                severity: Some(DiagnosticSeverity::HINT),
+                source: Some(DIAGNOSTIC_SOURCE.to_string()),
+                message: format!("翻译键 {} 未被使用", unused.key),
+                tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+                ..Default::default()
+            });
+        }
+
+        diagnostics
+    }
+
+    /// 发布所有诊断，并清除已经消失的诊断
+    fn publish_diagnostics(&mut self, connection: &Connection) -> Result<()> {
+        let mut diagnostics = self.diagnostics();
+        for path in self.published.drain() {
+            diagnostics.entry(path).or_default();
+        }
+
+        for (path, file_diagnostics) in diagnostics {
+            let Some(uri) = path_to_uri(&path) else {
+                continue;
+            };
+            if !file_diagnostics.is_empty() {
+                self.published.insert(path);
+            }
+            let params = PublishDiagnosticsParams::new(uri, file_diagnostics, None);
+            connection.sender.send(Message::Notification(
+                Notification::new(PublishDiagnostics::METHOD.to_string(), params)
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+/// 解析请求参数并调用处理函数生成响应
+fn respond<R>(request: Request, handler: impl FnOnce(R::Params) -> R::Result) -> Response
+where
+    R: lsp_types::request::Request,
+    R::Params: DeserializeOwned,
+    R::Result: Serialize,
+{
+    let id = request.id.clone();
+    match request.extract::<R::Params>(R::METHOD) {
+        Ok((id, params)) => Response::new_ok(id, handler(params)),
+        Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+    }
+}
+
+/// 解析通知参数
+fn extract_notification<N>(notification: Notification) -> Result<N::Params>
+where
+    N: lsp_types::notification::Notification,
+    N::Params: DeserializeOwned,
+{
+    Ok(notification.extract::<N::Params>(N::METHOD)?)
+}
+
+/// 光标之前的文本以未闭合的 `t!("` 结尾时，返回已经输入的键
+fn completion_prefix(before_cursor: &str) -> Option<&str> {
+    let regex = Regex::new(r#"\bt!\s*\(\s*"([^"]*)$"#).unwrap();
+    regex.captures(before_cursor)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str())
+}
+
+/// 包含指定字节位置的字符串字面量，返回字面量（含引号）的字节范围和内容
+fn string_literal_at(line: &str, offset: usize) -> Option<(usize, usize, String)> {
+    let mut start = None;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match (start, c) {
+            (None, '"') => start = Some(index),
+            (Some(_), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(open), '"') if !escaped => {
+                if open <= offset && offset <= index {
+                    return Some((open, index + 1, line[open + 1..index].to_string()));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+        escaped = false;
+    }
+    None
+}
+
+/// 诊断的范围：从 `t!` 开始到第一个字符串字面量结束；没有字面量时只覆盖 `t!`
+fn call_span(line: &str, column: usize) -> (usize, usize) {
+    let start = line.char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(0, |(index, _)| index);
+    let literal_end = line[start..].find('"')
+        .and_then(|open| string_literal_at(line, start + open))
+        .map(|(_, end, _)| end);
+    let end = literal_end.unwrap_or_else(|| (start + 2).min(line.len()));
+    (start, end)
+}
+
+/// 翻译键在翻译文件中的定义位置
+fn key_range(content: &str, key: &str) -> Option<Range> {
+    let (line_index, start, end) = locate_key(content, key)?;
+    let line = content.lines().nth(line_index)?;
+    Some(Range::new(
+        Position::new(line_index as u32, byte_to_utf16(line, start)),
+        Position::new(line_index as u32, byte_to_utf16(line, end)),
+    ))
+}
+
+/// 在翻译文件中查找翻译键的定义，返回行号（从 0 开始）以及键在该行中的字节范围
+///
+/// 先按扁平的键查找（`"user.profile": ...`），再依次查找嵌套结构中的每一段，
+/// 最后退回到任意位置的带引号的键（例如 Android 的 `name="user.profile"`）。
+fn locate_key(content: &str, key: &str) -> Option<(usize, usize, usize)> {
+    let lines: Vec<&str> = content.lines().collect();
+
+    let flat = lines.iter().enumerate()
+        .find_map(|(index, line)| key_on_line(line, key).map(|(start, end)| (index, start, end)));
+    if flat.is_some() {
+        return flat;
+    }
+
+    let mut from = 0;
+    let mut nested = None;
+    for segment in key.split('.') {
+        let found = lines.iter().enumerate().skip(from).find_map(|(index, line)| {
+            if let Some((start, end)) = key_on_line(line, segment) {
+                return Some((index, start, end, index + 1));
+            }
+            // TOML 的表头 `[user.profile]` 同时包含多段，下一段仍从这一行开始查找
+            let header = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+            header.split('.').any(|part| part.trim().trim_matches('"') == segment)
+                .then(|| line.find(segment).map(|start| (index, start, start + segment.len(), index)))
+                .flatten()
+        });
+        let Some((index, start, end, next)) = found else {
+            nested = None;
+            break;
+        };
+        nested = Some((index, start, end));
+        from = next;
+    }
+    if nested.is_some() {
+        return nested;
+    }
+
+    let quoted = format!("\"{}\"", key);
+    lines.iter().enumerate().find_map(|(index, line)| {
+        line.find(&quoted).map(|start| (index, start + 1, start + 1 + key.len()))
+    })
+}
+
+/// 这一行是否定义了名为 `name` 的键（`name:`、`"name":`、`name =`、Fluent 属性 `.name =`），返回键的字节范围
+fn key_on_line(line: &str, name: &str) -> Option<(usize, usize)> {
+    let body = line.trim_start();
+    let body = body.strip_prefix('.').unwrap_or(body);
+    let (start, rest) = match body.strip_prefix(['"', '\'']) {
+        Some(rest) => (line.len() - rest.len(), rest),
+        None => (line.len() - body.len(), body),
+    };
+
+    let rest = rest.strip_prefix(name)?;
+    let rest = rest.strip_prefix(['"', '\'']).unwrap_or(rest);
+    rest.trim_start().starts_with([':', '=']).then_some((start, start + name.len()))
+}
+
+/// 字符串的 UTF-16 长度（LSP 默认的位置编码）
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+/// 行内字节偏移对应的 UTF-16 位置
+fn byte_to_utf16(line: &str, offset: usize) -> u32 {
+    utf16_len(&line[..offset.min(line.len())])
+}
+
+/// 行内 UTF-16 位置对应的字节偏移
+fn utf16_to_byte(line: &str, character: u32) -> usize {
+    let mut units = 0;
+    for (index, c) in line.char_indices() {
+        if units >= character as usize {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+    line.len()
+}
+
+/// 把文件路径转换为 `file://` URI
+fn path_to_uri(path: &Path) -> Option<Uri> {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri.parse().ok()
+}
+
+/// 把 `file://` URI 转换为文件路径
+fn uri_to_path(uri: &Uri) -> Option<PathBuf> {
+    let encoded = uri.as_str().strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match (byte, tail.get(..2)) {
+            (b'%', Some(hex)) => {
+                let hex = std::str::from_utf8(hex).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_key() {
+        let yaml = "user:\n  profile:\n    title: 个人资料\n  name: 名字\n";
+        assert_eq!(locate_key(yaml, "user.name"), Some((3, 2, 6)));
+        assert_eq!(locate_key(yaml, "user.profile.title"), Some((2, 4, 9)));
+
+        let json = "{\n  \"user.name\": \"Name\"\n}\n";
+        assert_eq!(locate_key(json, "user.name"), Some((1, 3, 12)));
+
+        let toml = "[user.profile]\ntitle = \"Profile\"\n";
+        assert_eq!(locate_key(toml, "user.profile.title"), Some((1, 0, 5)));
+
+        let fluent = "login = Log in\n    .title = Sign in\n";
+        assert_eq!(locate_key(fluent, "login.title"), Some((1, 5, 10)));
+
+        assert_eq!(locate_key(yaml, "user.missing"), None);
+    }
+
+    #[test]
+    fn test_string_literal_and_completion_prefix() {
+        let line = r#"    println!("{}", t!("user.name", x = "a\"b"));"#;
+        let offset = line.find("user.name").unwrap() + 3;
+        assert_eq!(string_literal_at(line, offset).map(|(_, _, key)| key).as_deref(), Some("user.name"));
+        assert_eq!(call_span(line, 20), (19, 33));
+
+        assert_eq!(completion_prefix(r#"let a = t!("user."#), Some("user."));
+        assert_eq!(completion_prefix(r#"let a = t!("user.name")"#), None);
+
+        let path = Path::new("/tmp/项目 a/src/main.rs");
+        assert_eq!(uri_to_path(&path_to_uri(path).unwrap()).as_deref(), Some(path));
+    }
+}
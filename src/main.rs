@@ -1,15 +1,10 @@
-mod scanner;
-mod parser;
-mod analyzer;
-mod config;
-mod report;
-
+use i18n_audit::{analyzer, config, extract, lsp, parser, report, rename, scanner, spreadsheet, watch, xliff, AuditBuilder};
 use clap::{Parser, Subcommand};
 use anyhow::{Result, Context};
 use std::path::PathBuf;
 use walkdir::WalkDir;
-use regex::Regex;
 use std::fs;
+use std::io::Write;
 
 /// i18n-audit - 用于审计 rust-i18n 项目中未使用的翻译键
 #[derive(Parser, Debug)]
@@ -27,6 +22,18 @@ struct Cli {
     #[arg(long, default_value = "locales")]
     locales_dir: String,
     
+    /// 额外的翻译文件目录，可多次指定（例如 Fluent 的 i18n 目录）
+    #[arg(long = "extra-locales-dir")]
+    extra_locales_dirs: Vec<String>,
+    
+    /// 模板目录，扫描其中 .html/.jinja/.tera 模板中的翻译调用，可多次指定
+    #[arg(long = "template-dir")]
+    template_dirs: Vec<String>,
+    
+    /// 默认语言（源语言），默认为 i18n! 宏中的第一个 fallback 语言，否则为 en
+    #[arg(long)]
+    default_locale: Option<String>,
+    
     /// 警告阈值百分比，当未使用翻译键超过此百分比时发出警告
     #[arg(long, default_value_t = 20.0)]
     threshold: f32,
@@ -35,6 +42,30 @@ struct Cli {
     #[arg(long)]
     ignore_pattern: Option<String>,
     
+    /// 允许与默认语言相同的翻译值（例如品牌名），可多次指定
+    #[arg(long = "invariant-value")]
+    invariant_values: Vec<String>,
+    
+    /// 允许与默认语言相同的翻译键模式（正则表达式），可多次指定
+    #[arg(long = "invariant-key")]
+    invariant_keys: Vec<String>,
+    
+    /// 根据 feature 和 cfg 选项求值 cfg 属性，把只在禁用配置中使用的键单独报告
+    #[arg(long)]
+    evaluate_cfg: bool,
+    
+    /// 启用的 feature，可用逗号分隔或多次指定（隐含 --evaluate-cfg）
+    #[arg(long = "features")]
+    features: Vec<String>,
+    
+    /// cfg 选项，例如 unix 或 target_os="windows"，可多次指定（隐含 --evaluate-cfg）
+    #[arg(long = "cfg")]
+    cfg_options: Vec<String>,
+    
+    /// 计入“已使用”的使用上下文: production, test, example, bench, build，可多次指定
+    #[arg(long = "count-context", default_value = "production")]
+    count_contexts: Vec<scanner::UsageContext>,
+    
     /// 详细输出模式
     #[arg(short, long)]
     verbose: bool,
@@ -55,21 +86,118 @@ enum Commands {
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
+    /// 导出默认语言的翻译键及目标语言的当前翻译，交给翻译人员处理
+    Export {
+        /// 目标语言，导出 XLIFF 时必须指定
+        #[arg(short, long)]
+        language: Option<String>,
+        
+        /// 导出格式: xliff, csv
+        #[arg(short, long, default_value = "xliff")]
+        format: String,
+        
+        /// XLIFF 版本: 1.2, 2.0
+        #[arg(long, default_value = "1.2")]
+        xliff_version: String,
+        
+        /// 输出文件路径，如未指定则输出到控制台
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// 查找没有使用 t!() 的硬编码用户可见字符串，并建议翻译键
+    Extract {
+        /// 只检查这些文件或目录，默认为整个源代码目录
+        paths: Vec<PathBuf>,
+        
+        /// 检查的宏（不含 !），可多次指定
+        #[arg(long = "macro", default_values_t = extract::DEFAULT_MACROS.map(String::from))]
+        macros: Vec<String>,
+        
+        /// 检查的函数或方法，可多次指定
+        #[arg(long = "function", default_values_t = extract::DEFAULT_FUNCTIONS.map(String::from))]
+        functions: Vec<String>,
+        
+        /// 把字符串以建议的键写入默认语言的翻译文件
+        #[arg(long)]
+        add: bool,
+        
+        /// 把字符串改写为 t!() 调用，并把原文写入默认语言的翻译文件
+        #[arg(long)]
+        apply: bool,
+        
+        /// 把改写生成的补丁写入指定文件；未指定 --apply 时不修改任何文件
+        #[arg(long)]
+        patch: Option<PathBuf>,
+        
+        /// 输出格式: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    /// 在源代码和所有翻译文件中重命名翻译键，支持 user.* 形式的前缀重命名
+    Rename {
+        /// 旧的翻译键，或以 .* 结尾的前缀
+        old: String,
+        
+        /// 新的翻译键，或以 .* 结尾的前缀
+        new: String,
+        
+        /// 只输出补丁，不修改任何文件
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 列出翻译键的所有调用位置（文件:行:列）和所有定义
+    Where {
+        /// 翻译键
+        key: String,
+    },
+    /// 并排显示翻译键在所有语言中的值
+    Show {
+        /// 翻译键
+        key: String,
+    },
+    /// 通过标准输入输出运行语言服务器，在编辑器中显示诊断、悬停提示、跳转和补全
+    Lsp,
+    /// 监视源代码和翻译文件，每次修改后重新审计并输出结果的变化
+    Watch,
+    /// 将翻译人员返回的文件合并回翻译文件
+    Import {
+        /// 要导入的文件，.csv 文件按 CSV 矩阵导入，其余按 XLIFF 导入
+        input: PathBuf,
+        
+        /// 目标语言，如未指定则使用 XLIFF 文件中声明的目标语言
+        #[arg(short, long)]
+        language: Option<String>,
+    },
 }
 
+#[allow(clippy::needless_borrows_for_generic_args, clippy::collapsible_if)]
 fn main() -> Result<()> {
-    env_logger::init();
     let cli = Cli::parse();
+    // 详细输出模式下默认显示 info 级别的日志
+    let default_filter = if cli.verbose { "info" } else { "error" };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter)).init();
     
-    // 创建配置
-    let config = config::Config {
-        project_path: cli.path,
-        src_dir: cli.src_dir,
-        locales_dir: cli.locales_dir,
-        threshold: cli.threshold,
-        ignore_pattern: cli.ignore_pattern,
-        verbose: cli.verbose,
-    };
+    // 创建配置，并按 i18n.toml 和 i18n!() 宏调用确定翻译文件目录、默认语言和 minify_key 设置
+    let mut builder = AuditBuilder::new(cli.path)
+        .src_dir(cli.src_dir)
+        .locales_dir(cli.locales_dir)
+        .extra_locales_dirs(cli.extra_locales_dirs)
+        .template_dirs(cli.template_dirs)
+        .threshold(cli.threshold)
+        .invariant_values(cli.invariant_values)
+        .invariant_keys(cli.invariant_keys)
+        .evaluate_cfg(cli.evaluate_cfg)
+        .features(cli.features)
+        .cfg_options(cli.cfg_options)
+        .count_contexts(cli.count_contexts)
+        .verbose(cli.verbose);
+    if let Some(default_locale) = cli.default_locale {
+        builder = builder.default_locale(default_locale);
+    }
+    if let Some(ignore_pattern) = cli.ignore_pattern {
+        builder = builder.ignore_pattern(ignore_pattern);
+    }
+    let config = builder.build()?.into_config();
 
     match cli.command.unwrap_or(Commands::Run { 
         format: "text".to_string(), 
@@ -109,64 +237,7 @@ fn main() -> Result<()> {
             }
             
             // 2. 解析翻译文件，提取所有定义的翻译键
-            // 检查 rust_i18n::i18n!() 的调用并提取路径参数
-            let mut locales_dir = config.locales_dir.clone();
-            
-            // 先尝试扫描代码中的 i18n!() 调用获取实际路径
-            for entry in WalkDir::new(&config.src_path())
-                .follow_links(true)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| e.path().is_file())
-            {
-                let path = entry.path();
-                if let Some(ext) = path.extension() {
-                    if ext == "rs" {
-                        if let Ok(content) = fs::read_to_string(path) {
-                            // 查找 rust_i18n::i18n!("路径") 或 i18n!("路径")
-                            let i18n_regex = Regex::new(r#"(?:rust_i18n::)?i18n!\s*\(\s*"([^"]+)"#).unwrap();
-                            
-                            if let Some(caps) = i18n_regex.captures(&content) {
-                                if let Some(path_match) = caps.get(1) {
-                                    let i18n_path = path_match.as_str();
-                                    if config.verbose {
-                                        println!("检测到 i18n! 路径参数: {}", i18n_path);
-                                    }
-                                    
-                                    // 处理相对路径
-                                    if i18n_path.starts_with("../") {
-                                        let src_dir_path = config.project_path.join(&config.src_dir);
-                                        let src_parent = src_dir_path.parent()
-                                            .unwrap_or(&config.project_path);
-                                        
-                                        let abs_path = pathdiff::diff_paths(
-                                            src_parent.join(i18n_path.trim_start_matches("../")),
-                                            &config.project_path
-                                        ).unwrap_or_else(|| PathBuf::from(i18n_path));
-                                        
-                                        locales_dir = abs_path.to_string_lossy().to_string();
-                                        
-                                        if config.verbose {
-                                            println!("调整后的翻译文件路径: {}", locales_dir);
-                                        }
-                                        break;
-                                    } else {
-                                        locales_dir = i18n_path.to_string();
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            // 使用调整后的翻译文件路径
-            let mut adjusted_config = config.clone();
-            adjusted_config.locales_dir = locales_dir;
-            
-            let defined_keys = parser::parse_translation_files(&adjusted_config)
-                .context("解析翻译文件失败")?;
+            let defined_keys = load_translations(&config)?;
             
             if config.verbose {
                 println!("找到 {} 个已定义的翻译键", defined_keys.len());
@@ -198,7 +269,182 @@ fn main() -> Result<()> {
                 );
             }
         }
+        Commands::Export { language, format, xliff_version, output } => {
+            let defined_keys = load_translations(&config)?;
+            
+            let content = match format.as_str() {
+                "xliff" => {
+                    let Some(language) = &language else {
+                        anyhow::bail!("导出 XLIFF 时必须使用 --language 指定目标语言");
+                    };
+                    let version = xliff_version.parse()?;
+                    xliff::export(&defined_keys, &config.default_locale, language, version)?
+                }
+                "csv" => {
+                    let used_keys = scanner::scan_source_code(&config)
+                        .context("扫描源代码失败")?;
+                    let analysis_result = analyzer::analyze(&used_keys, &defined_keys, &config)
+                        .context("分析翻译键使用情况失败")?;
+                    spreadsheet::export(&defined_keys, &used_keys, &analysis_result, &config)?
+                }
+                _ => anyhow::bail!("不支持的导出格式: {}", format),
+            };
+            
+            if let Some(output_path) = &output {
+                fs::write(output_path, content)
+                    .with_context(|| format!("无法写入文件: {}", output_path.display()))?;
+                println!("已导出翻译到 {}", output_path.display());
+            } else {
+                print!("{}", content);
+            }
+        }
+        Commands::Extract { paths, macros, functions, add, apply, patch, format } => {
+            let defined_keys = load_translations(&config)?;
+            let options = extract::ExtractOptions { macros, functions, paths };
+            
+            let found = extract::find_hardcoded_strings(&config, &options, &defined_keys)
+                .context("查找硬编码字符串失败")?;
+            
+            let mut writer = std::io::stdout();
+            match format.as_str() {
+                "json" => writeln!(writer, "{}", serde_json::to_string_pretty(&found)?)?,
+                _ => report::print_hardcoded_strings(&mut writer, &found)?,
+            }
+            
+            if apply || patch.is_some() {
+                let plan = extract::plan_extraction(&found, &defined_keys, &config)?;
+                
+                if let Some(patch_path) = &patch {
+                    fs::write(patch_path, plan.changes.unified_diff(&config.project_path))
+                        .with_context(|| format!("无法写入文件: {}", patch_path.display()))?;
+                    println!("已生成补丁 {}", patch_path.display());
+                }
+                if apply {
+                    plan.changes.apply()?;
+                    println!(
+                        "已将 {} 个字符串改写为 t!() 调用，新增 {} 个翻译键",
+                        plan.rewritten, plan.added_keys
+                    );
+                }
+                if !plan.skipped.is_empty() {
+                    println!("以下字符串带有格式说明，需要手动改写:");
+                    for hardcoded in &plan.skipped {
+                        println!("  - {}:{} {}", hardcoded.file_path, hardcoded.line_number, hardcoded.text);
+                    }
+                }
+            } else if add {
+                let plan = extract::plan_locale_changes(&found, &defined_keys, &config)?;
+                plan.changes.apply()?;
+                println!("已将 {} 个字符串写入默认语言 {} 的翻译文件", plan.added_keys, config.default_locale);
+            } else if !found.is_empty() {
+                anyhow::bail!("发现 {} 个没有使用 t!() 的硬编码字符串", found.len());
+            }
+        }
+        Commands::Rename { old, new, dry_run } => {
+            let defined_keys = load_translations(&config)?;
+            let usages = scanner::scan_source_code(&config)
+                .context("扫描源代码失败")?;
+            
+            let plan = rename::plan_rename(&old, &new, &defined_keys, &usages, &config)?;
+            
+            if dry_run {
+                print!("{}", plan.changes.unified_diff(&config.project_path));
+            } else {
+                plan.changes.apply()?;
+                println!(
+                    "已重命名 {} 个翻译键，改写 {} 处调用",
+                    plan.renames.len(),
+                    plan.updated_call_sites
+                );
+            }
+            if !plan.manual_call_sites.is_empty() {
+                println!("以下调用没有以字面量引用翻译键，需要手动修改:");
+                for usage in &plan.manual_call_sites {
+                    println!("  - {}:{} {}", usage.file_path, usage.line_number, usage.key);
+                }
+            }
+        }
+        Commands::Where { key } => {
+            let defined_keys = load_translations(&config)?;
+            let usages = scanner::scan_source_code(&config)
+                .context("扫描源代码失败")?;
+            
+            let key_usages: Vec<&scanner::UsedKey> = usages.iter().filter(|u| u.key == key).collect();
+            let definitions: Vec<&parser::DefinedKey> = defined_keys.iter().filter(|k| k.key == key).collect();
+            if key_usages.is_empty() && definitions.is_empty() {
+                anyhow::bail!("没有找到翻译键: {}", key);
+            }
+            
+            report::print_key_references(&mut std::io::stdout(), &key, &key_usages, &definitions)?;
+        }
+        Commands::Show { key } => {
+            let defined_keys = load_translations(&config)?;
+            if !defined_keys.iter().any(|k| k.key == key) {
+                anyhow::bail!("没有找到翻译键: {}", key);
+            }
+            
+            report::print_key_values(&mut std::io::stdout(), &key, &defined_keys, &config.default_locale)?;
+        }
+        Commands::Lsp => {
+            lsp::run(config)?;
+        }
+        Commands::Watch => {
+            watch::run(config)?;
+        }
+        Commands::Import { input, language } => {
+            let defined_keys = load_translations(&config)?;
+            
+            let content = fs::read_to_string(&input)
+                .with_context(|| format!("无法读取文件: {}", input.display()))?;
+            
+            if input.extension().is_some_and(|ext| ext == "csv") {
+                let summary = spreadsheet::import(&content, &defined_keys, &config)?;
+                
+                println!("已导入 CSV: 更新 {} 个翻译", summary.updated);
+                if !summary.rejected.is_empty() {
+                    println!("以下行被拒绝:");
+                    for row in &summary.rejected {
+                        println!("  - 第 {} 行 ({}): {}", row.line, row.key, row.reason);
+                    }
+                    anyhow::bail!("有 {} 行未能导入", summary.rejected.len());
+                }
+                return Ok(());
+            }
+            
+            let document = xliff::parse(&content)
+                .with_context(|| format!("无法解析 XLIFF 文件: {}", input.display()))?;
+            
+            let language = language.unwrap_or_else(|| document.target_language.clone());
+            if language.is_empty() {
+                anyhow::bail!("XLIFF 文件未声明目标语言，请使用 --language 指定");
+            }
+            if !document.source_language.is_empty() && document.source_language != config.default_locale {
+                anyhow::bail!(
+                    "XLIFF 文件的源语言 ({}) 与默认语言 ({}) 不一致",
+                    document.source_language,
+                    config.default_locale
+                );
+            }
+            
+            let summary = xliff::import(&document, &language, &defined_keys, &config)?;
+            
+            println!(
+                "已导入 {} 的翻译: 更新 {} 个，跳过 {} 个未翻译单元",
+                language, summary.updated, summary.skipped
+            );
+            if !summary.unknown_keys.is_empty() {
+                println!("以下翻译键在默认语言中不存在，已跳过:");
+                for key in &summary.unknown_keys {
+                    println!("  - {}", key);
+                }
+            }
+        }
     }
 
     Ok(())
 }
+
+/// 解析所有翻译文件，返回已定义的翻译键
+fn load_translations(config: &config::Config) -> Result<Vec<parser::DefinedKey>> {
+    parser::parse_translation_files(config).context("解析翻译文件失败")
+}
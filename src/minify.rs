@@ -0,0 +1,137 @@
+//! rust-i18n `minify_key` 哈希键的计算
+//!
+//! 启用 `minify_key` 后，`t!("Hello world")` 查找的不是消息文本本身，而是由文本计算出的哈希键，
+//! 翻译文件也以哈希键为键。这里按 rust-i18n 的算法计算同样的哈希，以便与翻译文件中的键比对。
+
+use log::debug;
+use serde::{Serialize, Deserialize};
+use siphasher::sip128::SipHasher13;
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::config::Config;
+use crate::i18n_macro::I18nInvocation;
+
+/// rust-i18n 的 `minify_key` 设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinifyKeyOptions {
+    /// 是否启用 minify_key
+    pub enabled: bool,
+    /// 哈希键的长度
+    pub len: usize,
+    /// 哈希键的前缀
+    pub prefix: String,
+    /// 消息长度超过该阈值时才计算哈希，否则直接使用消息文本作为键
+    pub thresh: usize,
+}
+
+impl Default for MinifyKeyOptions {
+    fn default() -> Self {
+        MinifyKeyOptions {
+            enabled: false,
+            len: 24,
+            prefix: String::new(),
+            thresh: 127,
+        }
+    }
+}
+
+impl MinifyKeyOptions {
+    /// 计算消息文本对应的键；未启用 minify_key 时返回原文本
+    pub fn key_for(&self, message: &str) -> String {
+        if !self.enabled {
+            return message.to_string();
+        }
+        minify_key(message, self.len, &self.prefix, self.thresh)
+    }
+}
+
+/// 按 rust-i18n 的算法计算哈希键：SipHash-1-3 128 位哈希的 base62 编码，截取前 `len` 位并加上前缀
+pub fn minify_key(value: &str, len: usize, prefix: &str, thresh: usize) -> String {
+    if value.len() <= thresh {
+        return value.to_string();
+    }
+    let hash = SipHasher13::new().hash(value.as_bytes()).as_u128();
+    let encoded = base62::encode(hash);
+    let len = len.min(encoded.len());
+    format!("{}{}", prefix, &encoded[..len])
+}
+
+/// 读取项目的 minify_key 设置
+///
+/// 先读取 crate 的 Cargo.toml 中的 `[package.metadata.i18n]`，再以 `i18n!()` 宏的参数覆盖。
+pub fn detect_options(config: &Config, invocation: Option<&I18nInvocation>) -> MinifyKeyOptions {
+    let mut options = MinifyKeyOptions::default();
+
+    let crate_dir = invocation.map_or(config.project_path.as_path(), |i| i.crate_dir.as_path());
+    if let Ok(content) = fs::read_to_string(crate_dir.join("Cargo.toml"))
+        && let Ok(manifest) = toml::from_str::<toml::Value>(&content)
+        && let Some(metadata) = manifest.get("package")
+            .and_then(|p| p.get("metadata"))
+            .and_then(|m| m.get("i18n"))
+    {
+        if let Some(enabled) = metadata.get("minify-key").and_then(|v| v.as_bool()) {
+            options.enabled = enabled;
+        }
+        if let Some(len) = metadata.get("minify-key-len").and_then(|v| v.as_integer()) {
+            options.len = len as usize;
+        }
+        if let Some(prefix) = metadata.get("minify-key-prefix").and_then(|v| v.as_str()) {
+            options.prefix = prefix.to_string();
+        }
+        if let Some(thresh) = metadata.get("minify-key-thresh").and_then(|v| v.as_integer()) {
+            options.thresh = thresh as usize;
+        }
+    }
+
+    if let Some(invocation) = invocation {
+        apply_macro_options(&mut options, &invocation.options);
+    }
+
+    debug!("minify_key 设置: {:?}", options);
+    options
+}
+
+/// 以 `i18n!()` 宏参数中的 minify_key 设置覆盖默认值
+fn apply_macro_options(options: &mut MinifyKeyOptions, macro_options: &BTreeMap<String, String>) {
+    for (name, value) in macro_options {
+        match name.as_str() {
+            "minify_key" => options.enabled = value == "true",
+            "minify_key_len" => options.len = value.parse().unwrap_or(options.len),
+            "minify_key_prefix" => options.prefix = value.clone(),
+            "minify_key_thresh" => options.thresh = value.parse().unwrap_or(options.thresh),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minify_key() {
+        // 与 rust-i18n-support 的测试用例一致
+        assert_eq!(minify_key("Hello, world!", 24, "", 0), "1LokVzuiIrh1xByyZG4wjZ");
+        assert_eq!(minify_key("Hello, world!", 12, "", 0), "1LokVzuiIrh1");
+        assert_eq!(minify_key("Hello, world!", 12, "t_", 0), "t_1LokVzuiIrh1");
+        assert_eq!(minify_key("Hello, world!", 12, "t_", 128), "Hello, world!");
+    }
+
+    #[test]
+    fn test_apply_macro_options() {
+        let mut options = MinifyKeyOptions::default();
+        let macro_options = [("minify_key", "true"), ("minify_key_len", "12"), ("minify_key_prefix", "t_"), ("minify_key_thresh", "8")]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        apply_macro_options(&mut options, &macro_options);
+
+        assert_eq!(options, MinifyKeyOptions {
+            enabled: true,
+            len: 12,
+            prefix: "t_".to_string(),
+            thresh: 8,
+        });
+    }
+}
@@ -1,12 +1,15 @@
 use anyhow::{Result, Context, bail};
 use log::{info, debug};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use regex::Regex;
 use serde::{Serialize, Deserialize};
 use serde_yaml;
 use serde_json;
 use toml;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::config::Config;
@@ -26,71 +29,183 @@ pub struct DefinedKey {
 
 /// 解析翻译文件，提取所有定义的翻译键
 pub fn parse_translation_files(config: &Config) -> Result<Vec<DefinedKey>> {
-    let locales_path = config.locales_path();
-    info!("正在解析翻译文件目录: {}", locales_path.display());
-    
     let mut defined_keys = Vec::new();
     
+    for locales_path in config.locales_paths() {
+        parse_locales_dir(&locales_path, config, &mut defined_keys)?;
+    }
+    
+    info!("解析完成，找到 {} 个已定义的翻译键", defined_keys.len());
+    
+    Ok(defined_keys)
+}
+
+/// 翻译文件目录中所有支持的翻译文件
+pub fn locale_files(locales_path: &Path) -> Vec<PathBuf> {
     // 支持的翻译文件扩展名
-    let supported_extensions = [".yml", ".yaml", ".json", ".toml"];
+    let supported_extensions = [".yml", ".yaml", ".json", ".json5", ".toml", ".ftl", ".xml", ".strings"];
     
-    for entry in WalkDir::new(&locales_path)
+    WalkDir::new(locales_path)
         .follow_links(true)
         .into_iter()
         .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-        
-        if path.is_file() && path.extension()
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && path.extension()
             .and_then(|ext| ext.to_str())
-            .map(|ext| supported_extensions.iter().any(|&e| e == &format!(".{}", ext)))
-            .unwrap_or(false) 
-        {
-            debug!("处理翻译文件: {}", path.display());
+            .map(|ext| supported_extensions.iter().any(|&e| e == format!(".{}", ext)))
+            .unwrap_or(false))
+        .collect()
+}
+
+/// 解析单个翻译文件目录
+fn parse_locales_dir(locales_path: &Path, config: &Config, defined_keys: &mut Vec<DefinedKey>) -> Result<()> {
+    info!("正在解析翻译文件目录: {}", locales_path.display());
+    
+    for path in locale_files(locales_path) {
+        let path = path.as_path();
+        debug!("处理翻译文件: {}", path.display());
+        
+        // 从文件名或目录结构推断语言代码，移动端资源文件使用各自平台的目录约定
+        let language = match extract_platform_language(path, &config.default_locale) {
+            Some(language) => language,
+            None => extract_language_from_path(path, locales_path)?,
+        };
+        
+        // 读取文件内容
+        let relative_path = path.strip_prefix(&config.project_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
             
-            // 从文件名或目录结构推断语言代码
-            let language = extract_language_from_path(path, &locales_path)?;
+        let content = read_locale_file(path)?;
             
-            // 读取文件内容
-            let relative_path = path.strip_prefix(&config.project_path)
-                .unwrap_or(path)
-                .to_string_lossy()
-                .to_string();
-                
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("无法读取文件: {}", path.display()))?;
-                
-            // 根据文件扩展名选择合适的解析方法
-            match path.extension().and_then(|ext| ext.to_str()) {
-                Some("yml") | Some("yaml") => {
-                    parse_yaml(&content, &language, &relative_path, &mut defined_keys)?;
-                }
-                Some("json") => {
-                    parse_json(&content, &language, &relative_path, &mut defined_keys)?;
-                }
-                Some("toml") => {
-                    parse_toml(&content, &language, &relative_path, &mut defined_keys)?;
-                }
-                _ => {
-                    // 不应该发生，因为我们已经过滤了文件扩展名
-                    bail!("不支持的文件类型: {}", path.display());
-                }
+        // 根据文件扩展名选择合适的解析方法
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yml") | Some("yaml") => {
+                parse_yaml(&content, &language, &relative_path, defined_keys)?;
+            }
+            Some("json") => {
+                parse_json(&content, &language, &relative_path, defined_keys)?;
+            }
+            Some("toml") => {
+                parse_toml(&content, &language, &relative_path, defined_keys)?;
+            }
+            Some("ftl") => {
+                parse_fluent(&content, &language, &relative_path, defined_keys)?;
+            }
+            Some("json5") => {
+                parse_json5(&content, &language, &relative_path, defined_keys)?;
+            }
+            Some("xml") => {
+                parse_android_strings(&content, &language, &relative_path, defined_keys)?;
+            }
+            Some("strings") => {
+                parse_ios_strings(&content, &language, &relative_path, defined_keys)?;
+            }
+            _ => {
+                // 不应该发生，因为我们已经过滤了文件扩展名
+                bail!("不支持的文件类型: {}", path.display());
             }
         }
     }
     
-    info!("解析完成，找到 {} 个已定义的翻译键", defined_keys.len());
+    Ok(())
+}
+
+/// 提取翻译值中的占位符，统一为 `%{name}` 或 `{ $name }` 的写法
+///
+/// 支持 rust-i18n 的 `%{name}` 和 Fluent 的 `{ $name }` 两种写法；两种写法不能互换，
+/// 因此同名但写法不同的占位符视为不同的占位符。
+pub fn extract_placeholders(value: &str) -> BTreeSet<String> {
+    let placeholder_regex = Regex::new(r"%\{\s*([a-zA-Z0-9_]+)\s*\}|\{\s*\$([a-zA-Z0-9_-]+)\s*\}").unwrap();
     
-    Ok(defined_keys)
+    placeholder_regex.captures_iter(value)
+        .filter_map(|cap| match (cap.get(1), cap.get(2)) {
+            (Some(name), _) => Some(format!("%{{{}}}", name.as_str())),
+            (None, Some(name)) => Some(format!("{{ ${} }}", name.as_str())),
+            (None, None) => None,
+        })
+        .collect()
+}
+
+/// 读取翻译文件内容，iOS 的 .strings 文件常以带 BOM 的 UTF-16 编码保存
+fn read_locale_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("无法读取文件: {}", path.display()))?;
+    
+    let utf16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes.chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units)
+    };
+    
+    let content = match bytes.as_slice() {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes)?,
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes)?,
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec())?,
+        _ => String::from_utf8(bytes)?,
+    };
+    
+    Ok(content)
+}
+
+/// 按移动端平台的目录约定推断语言代码
+///
+/// - Android: `values/strings.xml` 为默认语言，`values-fr`、`values-zh-rCN`、`values-b+sr+Latn` 为对应语言
+/// - iOS: `Base.lproj` 为默认语言，`fr.lproj`、`zh-Hans.lproj` 为对应语言
+fn extract_platform_language(path: &Path, default_locale: &str) -> Option<String> {
+    let extension = path.extension().and_then(|ext| ext.to_str())?;
+    let dir_name = path.parent()?.file_name()?.to_str()?;
+    
+    match extension {
+        "xml" => {
+            let qualifiers = dir_name.strip_prefix("values")?;
+            
+            // BCP 47 形式的限定符: values-b+sr+Latn
+            if let Some(tag) = qualifiers.strip_prefix("-b+") {
+                return Some(tag.split('-').next().unwrap_or(tag).replace('+', "-"));
+            }
+            
+            let mut parts = qualifiers.split('-').skip(1);
+            match parts.next() {
+                Some(lang) if (2..=3).contains(&lang.len()) && lang.chars().all(|c| c.is_ascii_lowercase()) => {
+                    match parts.next() {
+                        Some(region) if region.len() == 3 && region.starts_with('r') => {
+                            Some(format!("{}-{}", lang, &region[1..]))
+                        }
+                        _ => Some(lang.to_string()),
+                    }
+                }
+                // 不含语言限定符的目录（values、values-night 等）属于默认语言
+                _ => Some(default_locale.to_string()),
+            }
+        }
+        "strings" => {
+            let lang = dir_name.strip_suffix(".lproj")?;
+            if lang == "Base" {
+                Some(default_locale.to_string())
+            } else {
+                Some(lang.to_string())
+            }
+        }
+        _ => None,
+    }
 }
 
 /// 从文件路径推断语言代码
+#[allow(clippy::collapsible_if)]
 fn extract_language_from_path(path: &Path, locales_path: &Path) -> Result<String> {
+    // Fluent 约定的目录结构为 {语言}/{模块}.ftl，文件名不是语言代码，需优先从目录推断
+    let is_fluent = path.extension().and_then(|ext| ext.to_str()) == Some("ftl");
+    
     // 首先尝试从文件名推断
-    if let Some(file_name) = path.file_stem().and_then(|s| s.to_str()) {
-        // 如果文件名就是语言代码 (例如：en.yml, zh-CN.json)
-        if file_name.len() <= 5 && !file_name.contains('.') {
-            return Ok(file_name.to_string());
+    if !is_fluent {
+        if let Some(file_name) = path.file_stem().and_then(|s| s.to_str()) {
+            // 如果文件名就是语言代码 (例如：en.yml, zh-CN.json)
+            if file_name.len() <= 5 && !file_name.contains('.') {
+                return Ok(file_name.to_string());
+            }
         }
     }
     
@@ -107,6 +222,15 @@ fn extract_language_from_path(path: &Path, locales_path: &Path) -> Result<String
         }
     }
     
+    // Fluent 文件也可能直接以语言命名 (例如：en-US.ftl)
+    if is_fluent {
+        if let Some(file_name) = path.file_stem().and_then(|s| s.to_str()) {
+            if file_name.len() <= 5 && !file_name.contains('.') {
+                return Ok(file_name.to_string());
+            }
+        }
+    }
+    
     // 如果无法推断，使用默认值
     debug!("无法从路径推断语言代码: {}，使用默认值 'unknown'", path.display());
     Ok("unknown".to_string())
@@ -191,7 +315,7 @@ fn extract_keys_from_json(
                         defined_keys.push(DefinedKey {
                             key: full_key.clone(),
                             language: language.to_string(),
-                            value: val.to_string(),
+                            value: val.as_str().map(|v| v.to_string()).unwrap_or_else(|| val.to_string()),
                             file_path: file_path.to_string(),
                         });
                     }
@@ -232,7 +356,7 @@ fn extract_keys_from_toml(
                         defined_keys.push(DefinedKey {
                             key: full_key.clone(),
                             language: language.to_string(),
-                            value: val.to_string(),
+                            value: val.as_str().map(|v| v.to_string()).unwrap_or_else(|| val.to_string()),
                             file_path: file_path.to_string(),
                         });
                     }
@@ -283,6 +407,24 @@ fn parse_json(content: &str, language: &str, file_path: &str, defined_keys: &mut
     let root: serde_json::Value = serde_json::from_str(content)
         .with_context(|| format!("无法解析 JSON 文件: {}", file_path))?;
 
+    extract_keys_from_json_root(root, language, file_path, defined_keys)
+}
+
+/// 解析 JSON5 文件
+fn parse_json5(content: &str, language: &str, file_path: &str, defined_keys: &mut Vec<DefinedKey>) -> Result<()> {
+    let root: serde_json::Value = json5::from_str(content)
+        .with_context(|| format!("无法解析 JSON5 文件: {}", file_path))?;
+
+    extract_keys_from_json_root(root, language, file_path, defined_keys)
+}
+
+/// 从 JSON 文档的顶层对象中提取所有键值对
+fn extract_keys_from_json_root(
+    root: serde_json::Value,
+    language: &str,
+    file_path: &str,
+    defined_keys: &mut Vec<DefinedKey>
+) -> Result<()> {
     if let serde_json::Value::Object(map) = root {
         // 如果顶层键是语言本身，则从下一层开始
         if map.len() == 1 && map.contains_key(language) {
@@ -322,4 +464,441 @@ fn parse_toml(content: &str, language: &str, file_path: &str, defined_keys: &mut
     }
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// 解析 Fluent (.ftl) 文件
+///
+/// 消息以 `id` 作为键，属性以 `id.attr` 作为键，术语保留前导的 `-`（例如 `-brand-name`）。
+/// 缩进的后续行视为上一个消息或属性值的续行。
+fn parse_fluent(content: &str, language: &str, file_path: &str, defined_keys: &mut Vec<DefinedKey>) -> Result<()> {
+    let entry_regex = Regex::new(r"^(-?[a-zA-Z][a-zA-Z0-9_-]*)\s*=\s*(.*)$")?;
+    let attribute_regex = Regex::new(r"^\.([a-zA-Z][a-zA-Z0-9_-]*)\s*=\s*(.*)$")?;
+    
+    // 当前消息 ID，以及正在收集的 (键, 值) 对
+    let mut current_id: Option<String> = None;
+    let mut pending: Option<(String, String)> = None;
+    
+    let mut flush = |pending: &mut Option<(String, String)>| {
+        if let Some((key, value)) = pending.take() {
+            // 只有属性而没有值的消息不作为独立的键
+            if !value.trim().is_empty() {
+                defined_keys.push(DefinedKey {
+                    key,
+                    language: language.to_string(),
+                    value: value.trim().to_string(),
+                    file_path: file_path.to_string(),
+                });
+            }
+        }
+    };
+    
+    for (line_idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        
+        // 空行不会终止多行值，但注释会终止当前条目
+        if trimmed.is_empty() {
+            continue;
+        }
+        
+        if !line.starts_with([' ', '\t']) {
+            flush(&mut pending);
+            current_id = None;
+            
+            if trimmed.starts_with('#') {
+                continue;
+            }
+            
+            match entry_regex.captures(line) {
+                Some(caps) => {
+                    let id = caps[1].to_string();
+                    pending = Some((id.clone(), caps[2].to_string()));
+                    current_id = Some(id);
+                }
+                None => {
+                    bail!("无法解析 Fluent 文件 {} 第 {} 行: {}", file_path, line_idx + 1, line);
+                }
+            }
+            continue;
+        }
+        
+        let Some(id) = &current_id else {
+            bail!("无法解析 Fluent 文件 {} 第 {} 行: 缩进行不属于任何消息", file_path, line_idx + 1);
+        };
+        
+        if let Some(caps) = attribute_regex.captures(trimmed) {
+            flush(&mut pending);
+            pending = Some((format!("{}.{}", id, &caps[1]), caps[2].to_string()));
+        } else if let Some((_, value)) = pending.as_mut() {
+            // 多行值或选择表达式的续行
+            if !value.is_empty() {
+                value.push('\n');
+            }
+            value.push_str(trimmed);
+        }
+    }
+    
+    flush(&mut pending);
+    
+    Ok(())
+}
+
+/// 解析 Android 的 strings.xml 资源文件
+///
+/// `<string>` 和 `<plurals>` 以 `name` 属性作为键（复数取 `other` 数量的值），
+/// `<string-array>` 的各项以换行拼接。根元素不是 `<resources>` 的 XML 文件会被忽略。
+fn parse_android_strings(content: &str, language: &str, file_path: &str, defined_keys: &mut Vec<DefinedKey>) -> Result<()> {
+    let mut reader = Reader::from_str(content);
+    
+    let mut is_resources = false;
+    // 当前资源: (元素名, name 属性)
+    let mut current: Option<(String, String)> = None;
+    // 当前 <item> 的 quantity 属性
+    let mut item_quantity: Option<String> = None;
+    let mut items: Vec<(Option<String>, String)> = Vec::new();
+    let mut text = String::new();
+    
+    loop {
+        match reader.read_event().with_context(|| format!("无法解析 Android 资源文件: {}", file_path))? {
+            Event::Start(element) => {
+                let name = element.local_name();
+                let name = name.as_ref();
+                
+                if !is_resources {
+                    if name != "resources" {
+                        debug!("不是 Android 资源文件，跳过: {}", file_path);
+                        return Ok(());
+                    }
+                    is_resources = true;
+                    continue;
+                }
+                
+                match (name, &current) {
+                    ("string" | "plurals" | "string-array", None) => {
+                        let Some(resource_name) = xml_attribute(&element, "name")? else {
+                            continue;
+                        };
+                        current = Some((name.to_string(), resource_name));
+                        items.clear();
+                        text.clear();
+                    }
+                    ("item", Some(_)) => {
+                        item_quantity = xml_attribute(&element, "quantity")?;
+                        text.clear();
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(t) if current.is_some() => {
+                text.push_str(&t.xml10_content());
+            }
+            Event::CData(t) if current.is_some() => {
+                text.push_str(&t.xml10_content());
+            }
+            Event::GeneralRef(r) if current.is_some() => {
+                if let Some(ch) = r.resolve_char_ref()? {
+                    text.push(ch);
+                } else if let Some(resolved) = quick_xml::escape::resolve_predefined_entity(&r.xml10_content()) {
+                    text.push_str(resolved);
+                }
+            }
+            Event::End(element) => {
+                let name = element.local_name();
+                match (name.as_ref(), current.take()) {
+                    ("item", Some(resource)) => {
+                        items.push((item_quantity.take(), unescape_android(&text)));
+                        text.clear();
+                        current = Some(resource);
+                    }
+                    (end, Some((kind, key))) if end == kind => {
+                        let value = match kind.as_str() {
+                            "string" => unescape_android(&text),
+                            "plurals" => items.iter()
+                                .find(|(quantity, _)| quantity.as_deref() == Some("other"))
+                                .or(items.first())
+                                .map(|(_, value)| value.clone())
+                                .unwrap_or_default(),
+                            _ => items.iter()
+                                .map(|(_, value)| value.as_str())
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                        };
+                        
+                        defined_keys.push(DefinedKey {
+                            key,
+                            language: language.to_string(),
+                            value,
+                            file_path: file_path.to_string(),
+                        });
+                    }
+                    // 嵌套的内联元素（例如 <xliff:g>）
+                    (_, resource) => current = resource,
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    
+    Ok(())
+}
+
+/// 读取 XML 元素的某个属性值
+fn xml_attribute(element: &BytesStart, name: &str) -> Result<Option<String>> {
+    for attr in element.attributes() {
+        let attr = attr?;
+        if attr.key.local_name().as_ref() == name {
+            return Ok(Some(attr.normalized_value(XmlVersion::Implicit1_0)?.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// 处理 Android 字符串资源中的转义和引号
+fn unescape_android(value: &str) -> String {
+    let value = value.trim();
+    let value = value.strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// 解析 iOS 的 .strings 文件: `"key" = "value";`，支持 `/* */` 和 `//` 注释
+fn parse_ios_strings(content: &str, language: &str, file_path: &str, defined_keys: &mut Vec<DefinedKey>) -> Result<()> {
+    let mut chars = content.chars().peekable();
+    
+    // 读取一个带引号的字符串，或者不带引号的标识符
+    fn read_token(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+        let mut token = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => return Some(token),
+                    '\\' => match chars.next()? {
+                        'n' => token.push('\n'),
+                        't' => token.push('\t'),
+                        'r' => token.push('\r'),
+                        'U' | 'u' => {
+                            let hex: String = (0..4).filter_map(|_| chars.next()).collect();
+                            token.push(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)?);
+                        }
+                        other => token.push(other),
+                    },
+                    _ => token.push(c),
+                }
+            }
+            None
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' {
+                    token.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            (!token.is_empty()).then_some(token)
+        }
+    }
+    
+    // 跳过空白和注释
+    fn skip_trivia(chars: &mut std::iter::Peekable<std::str::Chars>) {
+        loop {
+            match chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    chars.next();
+                }
+                Some('/') => {
+                    let mut lookahead = chars.clone();
+                    lookahead.next();
+                    match lookahead.next() {
+                        Some('/') => {
+                            for c in chars.by_ref() {
+                                if c == '\n' {
+                                    break;
+                                }
+                            }
+                        }
+                        Some('*') => {
+                            chars.next();
+                            chars.next();
+                            let mut prev = ' ';
+                            for c in chars.by_ref() {
+                                if prev == '*' && c == '/' {
+                                    break;
+                                }
+                                prev = c;
+                            }
+                        }
+                        _ => return,
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+    
+    loop {
+        skip_trivia(&mut chars);
+        if chars.peek().is_none() {
+            break;
+        }
+        
+        let Some(key) = read_token(&mut chars) else {
+            bail!("无法解析 .strings 文件 {}: 无效的键", file_path);
+        };
+        skip_trivia(&mut chars);
+        if chars.next() != Some('=') {
+            bail!("无法解析 .strings 文件 {}: 键 {} 之后缺少 =", file_path, key);
+        }
+        skip_trivia(&mut chars);
+        let Some(value) = read_token(&mut chars) else {
+            bail!("无法解析 .strings 文件 {}: 键 {} 的值无效", file_path, key);
+        };
+        skip_trivia(&mut chars);
+        if chars.next() != Some(';') {
+            bail!("无法解析 .strings 文件 {}: 键 {} 之后缺少 ;", file_path, key);
+        }
+        
+        defined_keys.push(DefinedKey {
+            key,
+            language: language.to_string(),
+            value,
+            file_path: file_path.to_string(),
+        });
+    }
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_fluent() {
+        let content = r#"
+# 注释
+-brand-name = Foo
+hello-world = 你好，世界！
+login-input =
+    .placeholder = 邮箱地址
+    .aria-label = 登录输入框
+emails =
+    { $count ->
+        [one] 一封新邮件
+       *[other] { $count } 封新邮件
+    }
+"#;
+        let mut defined_keys = Vec::new();
+        
+        parse_fluent(content, "zh-CN", "i18n/zh-CN/app.ftl", &mut defined_keys).unwrap();
+        
+        let keys: Vec<&str> = defined_keys.iter().map(|k| k.key.as_str()).collect();
+        assert_eq!(keys, vec!["-brand-name", "hello-world", "login-input.placeholder", "login-input.aria-label", "emails"]);
+        assert_eq!(defined_keys[1].value, "你好，世界！");
+        assert!(defined_keys[4].value.contains("[one] 一封新邮件"));
+    }
+    
+    #[test]
+    fn test_extract_language_from_fluent_path() {
+        let locales_path = Path::new("i18n");
+        
+        assert_eq!(extract_language_from_path(Path::new("i18n/en-US/app.ftl"), locales_path).unwrap(), "en-US");
+        assert_eq!(extract_language_from_path(Path::new("i18n/fr.ftl"), locales_path).unwrap(), "fr");
+        assert_eq!(extract_language_from_path(Path::new("i18n/zh-CN.yml"), locales_path).unwrap(), "zh-CN");
+    }
+    
+    #[test]
+    fn test_parse_json5() {
+        let content = r#"
+// 注释
+{
+  en: {
+    /* 登录页 */
+    auth: {
+      login: { title: 'Sign in', },
+    },
+    "menu.file": "File",
+    count: 3,
+  },
+}
+"#;
+        let mut defined_keys = Vec::new();
+        
+        parse_json5(content, "en", "locales/en.json5", &mut defined_keys).unwrap();
+        
+        let mut entries: Vec<(&str, &str)> = defined_keys.iter().map(|k| (k.key.as_str(), k.value.as_str())).collect();
+        entries.sort();
+        assert_eq!(entries, vec![("auth.login.title", "Sign in"), ("count", "3"), ("menu.file", "File")]);
+    }
+    
+    #[test]
+    fn test_parse_android_strings() {
+        let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<resources xmlns:xliff="urn:oasis:names:tc:xliff:document:1.2">
+    <string name="auth.login.title">Sign in</string>
+    <string name="welcome">Hello, <xliff:g id="name">%1$s</xliff:g>! It\'s &amp; ok</string>
+    <plurals name="emails">
+        <item quantity="one">One email</item>
+        <item quantity="other">%d emails</item>
+    </plurals>
+    <color name="primary">#FF0000</color>
+</resources>
+"#;
+        let mut defined_keys = Vec::new();
+        
+        parse_android_strings(content, "en", "res/values/strings.xml", &mut defined_keys).unwrap();
+        
+        let entries: Vec<(&str, &str)> = defined_keys.iter().map(|k| (k.key.as_str(), k.value.as_str())).collect();
+        assert_eq!(entries, vec![
+            ("auth.login.title", "Sign in"),
+            ("welcome", "Hello, %1$s! It's & ok"),
+            ("emails", "%d emails"),
+        ]);
+    }
+    
+    #[test]
+    fn test_parse_ios_strings() {
+        let content = r#"
+/* 登录页标题 */
+"auth.login.title" = "Sign in";
+// 带转义的值
+"welcome" = "Say \"hi\"\n";
+"#;
+        let mut defined_keys = Vec::new();
+        
+        parse_ios_strings(content, "en", "fr.lproj/Localizable.strings", &mut defined_keys).unwrap();
+        
+        let entries: Vec<(&str, &str)> = defined_keys.iter().map(|k| (k.key.as_str(), k.value.as_str())).collect();
+        assert_eq!(entries, vec![("auth.login.title", "Sign in"), ("welcome", "Say \"hi\"\n")]);
+    }
+    
+    #[test]
+    fn test_extract_platform_language() {
+        let lang = |path: &str| extract_platform_language(Path::new(path), "en");
+        
+        assert_eq!(lang("res/values/strings.xml").as_deref(), Some("en"));
+        assert_eq!(lang("res/values-fr/strings.xml").as_deref(), Some("fr"));
+        assert_eq!(lang("res/values-zh-rCN/strings.xml").as_deref(), Some("zh-CN"));
+        assert_eq!(lang("res/values-b+sr+Latn/strings.xml").as_deref(), Some("sr-Latn"));
+        assert_eq!(lang("ios/Base.lproj/Localizable.strings").as_deref(), Some("en"));
+        assert_eq!(lang("ios/zh-Hans.lproj/Localizable.strings").as_deref(), Some("zh-Hans"));
+        assert_eq!(lang("locales/en.yml"), None);
+    }
+}
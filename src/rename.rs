@@ -0,0 +1,200 @@
+//! 在源代码和所有翻译文件中重命名翻译键
+//!
+//! 支持重命名单个键（`user.profile` → `account.profile`），也支持以 `.*` 结尾的前缀重命名
+//! （`user.*` → `account.*`）。源代码中只改写以字面量引用该键的调用，
+//! 通过常量、变量等间接引用的调用列出来由用户手动处理。
+
+use anyhow::{Result, bail};
+use log::info;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crate::changes::FileChanges;
+use crate::config::Config;
+use crate::locale_writer;
+use crate::parser::DefinedKey;
+use crate::scanner::{KeyResolution, UsedKey};
+
+/// 在调用所在行之后最多查找的行数，用于跨多行的 `t!()` 调用
+const CALL_SEARCH_LINES: usize = 5;
+
+/// 重命名翻译键需要做的修改
+#[derive(Debug, Default)]
+pub struct RenamePlan {
+    /// 旧键到新键的映射
+    pub renames: BTreeMap<String, String>,
+    /// 对源代码和翻译文件的修改
+    pub changes: FileChanges,
+    /// 改写的调用数量
+    pub updated_call_sites: usize,
+    /// 需要手动修改的调用（通过常量、变量等引用，或者没有找到字面量）
+    pub manual_call_sites: Vec<UsedKey>,
+}
+
+/// 计算把 `old` 重命名为 `new` 所需的修改
+///
+/// 任何一个新键已经存在于某种语言中时拒绝重命名。
+pub fn plan_rename(
+    old: &str,
+    new: &str,
+    defined_keys: &[DefinedKey],
+    usages: &[UsedKey],
+    config: &Config,
+) -> Result<RenamePlan> {
+    if old.ends_with(".*") != new.ends_with(".*") {
+        bail!("前缀重命名时新旧键都必须以 .* 结尾: {} -> {}", old, new);
+    }
+    if old == new {
+        bail!("新旧翻译键相同: {}", old);
+    }
+
+    let mut plan = RenamePlan::default();
+    for key in defined_keys.iter().map(|k| &k.key).chain(usages.iter().map(|u| &u.key)) {
+        if let Some(renamed) = renamed_key(key, old, new) {
+            plan.renames.insert(key.clone(), renamed);
+        }
+    }
+    if plan.renames.is_empty() {
+        bail!("没有找到翻译键: {}", old);
+    }
+
+    let existing: BTreeSet<&str> = defined_keys.iter()
+        .map(|k| k.key.as_str())
+        .filter(|key| plan.renames.values().any(|new_key| new_key == key))
+        .collect();
+    if !existing.is_empty() {
+        bail!("目标翻译键已存在: {}", existing.into_iter().collect::<Vec<_>>().join(", "));
+    }
+
+    // 翻译文件：每种语言中的定义都移动到新键
+    for def_key in defined_keys {
+        let Some(new_key) = plan.renames.get(&def_key.key) else {
+            continue;
+        };
+        // Fluent 属性随消息一起重命名
+        let is_fluent = def_key.file_path.ends_with(".ftl");
+        if is_fluent && def_key.key.contains('.') {
+            continue;
+        }
+
+        let path = config.project_path.join(&def_key.file_path);
+        let updated = locale_writer::rename_key(
+            &path,
+            &plan.changes.content(&path),
+            &def_key.language,
+            &def_key.key,
+            new_key,
+            &def_key.value,
+        )?;
+        plan.changes.update(&path, updated);
+    }
+
+    // 源代码：改写以字面量引用旧键的调用
+    let mut edited: HashSet<(&str, usize, usize, &str)> = HashSet::new();
+    for usage in usages {
+        let Some(new_key) = plan.renames.get(&usage.key) else {
+            continue;
+        };
+        if usage.resolution != KeyResolution::Literal {
+            plan.manual_call_sites.push(usage.clone());
+            continue;
+        }
+        if !edited.insert((&usage.file_path, usage.line_number, usage.column, &usage.key)) {
+            continue;
+        }
+
+        let path = config.project_path.join(&usage.file_path);
+        match rename_in_source(&plan.changes.content(&path), usage.line_number, usage.column, &usage.key, new_key) {
+            Some(updated) => {
+                plan.changes.update(&path, updated);
+                plan.updated_call_sites += 1;
+            }
+            None => plan.manual_call_sites.push(usage.clone()),
+        }
+    }
+
+    info!(
+        "重命名 {} 个翻译键，改写 {} 处调用，{} 处需要手动修改",
+        plan.renames.len(),
+        plan.updated_call_sites,
+        plan.manual_call_sites.len()
+    );
+    Ok(plan)
+}
+
+/// 计算某个键重命名后的键；不受影响时返回 None
+fn renamed_key(key: &str, old: &str, new: &str) -> Option<String> {
+    match (old.strip_suffix('*'), new.strip_suffix('*')) {
+        (Some(old_prefix), Some(new_prefix)) if old.ends_with(".*") => {
+            key.strip_prefix(old_prefix).map(|rest| format!("{}{}", new_prefix, rest))
+        }
+        _ => (key == old).then(|| new.to_string()),
+    }
+}
+
+/// 把源代码中从 `line_number` 行 `column` 列开始的调用里引用 `old` 的字符串字面量改为 `new`
+///
+/// 只替换调用开头之后的第一个字面量，即 `t!()` 的键参数；跨多行的调用只在起始行之后的几行中查找。
+/// 返回修改后的内容，没有找到字面量时返回 None。
+fn rename_in_source(content: &str, line_number: usize, column: usize, old: &str, new: &str) -> Option<String> {
+    let literal_regex = Regex::new(&format!(r#"(["']){}(["'])"#, regex::escape(old))).unwrap();
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let start = line_number.checked_sub(1)?;
+    let line = lines.get(start)?;
+    let line_offset: usize = lines[..start].iter().map(|line| line.len()).sum();
+    let call_offset = line_offset + line.char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(0, |(index, _)| index);
+    let window_end = line_offset + lines[start..(start + 1 + CALL_SEARCH_LINES).min(lines.len())]
+        .iter()
+        .map(|line| line.len())
+        .sum::<usize>();
+
+    let literal = literal_regex.captures_iter(&content[call_offset..window_end])
+        .find(|caps| caps[1] == caps[2])?;
+    let quote = &literal[1];
+    let range = literal.get(0).unwrap().range();
+    Some(format!(
+        "{}{}{}{}{}",
+        &content[..call_offset + range.start],
+        quote,
+        new,
+        quote,
+        &content[call_offset + range.end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renamed_key() {
+        assert_eq!(renamed_key("user.profile", "user.profile", "account.profile").as_deref(), Some("account.profile"));
+        assert_eq!(renamed_key("user.profile.title", "user.*", "account.*").as_deref(), Some("account.profile.title"));
+        assert_eq!(renamed_key("username", "user.*", "account.*"), None);
+        assert_eq!(renamed_key("user.profile.title", "user.profile", "account.profile"), None);
+    }
+
+    #[test]
+    fn test_rename_in_source() {
+        let content = "fn main() {\n    let a = t!(\"user.profile\");\n    view! {\n        {t!(\n            \"user.profile\",\n        )}\n    }\n}\n";
+
+        let updated = rename_in_source(content, 2, 13, "user.profile", "account.profile").unwrap();
+        assert!(updated.contains("let a = t!(\"account.profile\");"));
+        assert!(updated.contains("        \"user.profile\",\n"));
+
+        let updated = rename_in_source(content, 4, 10, "user.profile", "account.profile").unwrap();
+        assert!(updated.contains("        \"account.profile\",\n"));
+        assert!(rename_in_source(content, 8, 1, "user.profile", "account.profile").is_none());
+    }
+
+    #[test]
+    fn test_rename_in_source_only_renames_the_call_argument() {
+        let content = "log(\"user.profile\", t!(\"user.profile\"), \"user.profile\");\n";
+
+        let updated = rename_in_source(content, 1, 22, "user.profile", "account.profile").unwrap();
+        assert_eq!(updated, "log(\"user.profile\", t!(\"account.profile\"), \"user.profile\");\n");
+    }
+}
@@ -6,7 +6,11 @@ use std::io::Write;
 use std::path::Path;
 use prettytable::{row, Cell, Row, Table};
 
-use crate::analyzer::{AnalysisResult, MissingKey, UnusedKey};
+use crate::analyzer::{AnalysisResult, DynamicKey, MissingKey, Severity, UnusedKey};
+use crate::extract::HardcodedString;
+use crate::parser::DefinedKey;
+use crate::scanner::{UsageContext, UsedKey};
+use crate::watch::FindingChange;
 
 pub fn print_text_report(writer: &mut dyn Write, result: &AnalysisResult, threshold: f32) -> Result<()> {
     writeln!(writer, "\n{}", "I18n 翻译键审计报告".bold().underline())?;
@@ -48,6 +52,66 @@ pub fn print_text_report(writer: &mut dyn Write, result: &AnalysisResult, thresh
             ]);
         }
         table.print(writer)?;
+        
+        // 打印每个动态键模式保留的翻译键
+        if result.dynamic_keys.iter().any(|k| !k.matched_keys.is_empty()) {
+            writeln!(writer, "\n{}", "动态键保留的翻译键:".cyan().bold())?;
+            print_dynamic_matches_table(writer, &result.dynamic_keys)?;
+        }
+    }
+
+    // 打印只在禁用配置中使用的键
+    if !result.conditional_keys.is_empty() {
+        writeln!(
+            writer,
+            "\n{}",
+            "只在禁用配置中使用的键 (当前 feature/cfg 下不会被使用):".magenta().bold()
+        )?;
+        let mut table = Table::new();
+        table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
+        table.set_titles(row![b => "翻译键", "cfg 条件", "位置"]);
+        
+        for key in &result.conditional_keys {
+            table.add_row(row![
+                key.key,
+                key.condition,
+                format!("{}:{}", key.file_path, key.line_number)
+            ]);
+        }
+        table.print(writer)?;
+    }
+
+    // 打印只在测试、示例等上下文中使用的键
+    if !result.context_only_keys.is_empty() {
+        writeln!(
+            writer,
+            "\n{}",
+            "只在测试、示例等代码中使用的键 (不计入已使用):".magenta().bold()
+        )?;
+        let mut table = Table::new();
+        table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
+        table.set_titles(row![b => "翻译键", "上下文", "位置"]);
+        
+        for key in &result.context_only_keys {
+            table.add_row(row![
+                key.key,
+                key.context.label(),
+                format!("{}:{}", key.file_path, key.line_number)
+            ]);
+        }
+        table.print(writer)?;
+    }
+
+    // 打印翻译键使用热度
+    if !result.most_used_keys.is_empty() {
+        writeln!(writer, "\n{}", "翻译键使用热度:".blue().bold())?;
+        print_usage_heatmap_table(writer, result)?;
+    }
+
+    // 打印翻译完整度及与默认语言相同的未翻译值
+    if !result.completeness.is_empty() {
+        writeln!(writer, "\n{}", "翻译完整度:".blue().bold())?;
+        print_completeness_table(writer, result)?;
     }
 
     // 打印建议
@@ -91,6 +155,15 @@ fn print_stats_table(writer: &mut dyn Write, result: &AnalysisResult) -> Result<
     table.add_row(row![
         "动态键", result.total_dynamic.to_string().cyan()
     ]);
+    table.add_row(row![
+        "未翻译的值", result.total_untranslated.to_string().blue()
+    ]);
+    table.add_row(row![
+        "条件使用的键", result.total_conditional.to_string().magenta()
+    ]);
+    table.add_row(row![
+        "仅测试等使用的键", result.total_context_only.to_string().magenta()
+    ]);
     table.add_row(row![
         "未使用比例", format!("{:.2}%", result.unused_percentage).yellow()
     ]);
@@ -128,24 +201,270 @@ fn print_unused_keys_table(
     Ok(())
 }
 
+/// 打印缺少翻译的键（每个键最多列出前若干个调用位置）
 fn print_missing_keys_table(writer: &mut dyn Write, missing_keys: &[MissingKey]) -> Result<()> {
+    const MAX_LISTED_SITES: usize = 5;
+
     let mut table = Table::new();
     table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
-    table.set_titles(row![b => "翻译键", "位置", "缺少的语言"]);
+    table.set_titles(row![b => "翻译键", "调用次数", "位置", "缺少的语言", "严重程度"]);
 
     for key in missing_keys {
+        let severity = match key.severity {
+            Severity::High => "高 (显式指定语言)".red().bold(),
+            Severity::Normal => "普通".normal(),
+        };
+        // 启用 minify_key 时同时显示哈希键和原始消息
+        let key_text = match &key.message {
+            Some(message) => format!("{}\n({})", key.key, message),
+            None => key.key.clone(),
+        };
+        // 旧版本的 JSON 报告中没有调用位置列表
+        let mut locations = if key.call_sites.is_empty() {
+            format!("{}:{}", key.file_path, key.line_number)
+        } else {
+            key.call_sites.iter()
+                .take(MAX_LISTED_SITES)
+                .map(|site| format!("{}:{}:{}", site.file_path, site.line_number, site.column))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        if key.call_sites.len() > MAX_LISTED_SITES {
+            locations.push_str(&format!("\n... 另外 {} 处", key.call_sites.len() - MAX_LISTED_SITES));
+        }
+        table.add_row(row![
+            key_text,
+            key.call_sites.len().max(1).to_string(),
+            locations,
+            key.missing_languages.join(", ").red(),
+            severity
+        ]);
+    }
+    
+    table.print(writer)?;
+    Ok(())
+}
+
+/// 打印使用次数最多和最少的翻译键
+fn print_usage_heatmap_table(writer: &mut dyn Write, result: &AnalysisResult) -> Result<()> {
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
+    table.set_titles(row![b => "使用最多的键", "调用次数", "使用最少的键", "调用次数"]);
+
+    for index in 0..result.most_used_keys.len().max(result.least_used_keys.len()) {
+        let most = result.most_used_keys.get(index);
+        let least = result.least_used_keys.get(index);
         table.add_row(row![
-            key.key,
-            format!("{}:{}", key.file_path, key.line_number),
-            key.missing_languages.join(", ").red()
+            most.map_or("", |k| k.key.as_str()),
+            most.map_or(String::new(), |k| k.usage_count.to_string()).green(),
+            least.map_or("", |k| k.key.as_str()),
+            least.map_or(String::new(), |k| k.usage_count.to_string()).yellow()
         ]);
     }
+    table.print(writer)?;
+
+    Ok(())
+}
+
+/// 打印每个动态键模式保留的翻译键（每个模式最多列出前若干个）
+fn print_dynamic_matches_table(writer: &mut dyn Write, dynamic_keys: &[DynamicKey]) -> Result<()> {
+    const MAX_LISTED_KEYS: usize = 10;
     
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
+    table.set_titles(row![b => "动态键模式", "保留的键数量", "保留的翻译键"]);
+    
+    for key in dynamic_keys.iter().filter(|k| !k.matched_keys.is_empty()) {
+        let mut listed = key.matched_keys.iter()
+            .take(MAX_LISTED_KEYS)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        if key.matched_keys.len() > MAX_LISTED_KEYS {
+            listed.push_str(&format!("\n... 另外 {} 个", key.matched_keys.len() - MAX_LISTED_KEYS));
+        }
+        
+        table.add_row(row![
+            key.pattern,
+            key.matched_keys.len().to_string().yellow(),
+            listed
+        ]);
+    }
     table.print(writer)?;
+    
+    Ok(())
+}
+
+fn print_completeness_table(writer: &mut dyn Write, result: &AnalysisResult) -> Result<()> {
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
+    table.set_titles(row![b => "语言", "完整度", "已翻译", "与默认语言相同"]);
+
+    for completeness in &result.completeness {
+        let percentage = format!("{:.2}%", completeness.percentage);
+        table.add_row(row![
+            completeness.language,
+            if completeness.untranslated_keys.is_empty() { percentage.green() } else { percentage.yellow() },
+            format!("{}/{}", completeness.translated_keys, completeness.total_keys),
+            completeness.untranslated_keys.len()
+        ]);
+    }
+    table.print(writer)?;
+
+    if result.total_untranslated == 0 {
+        return Ok(());
+    }
+
+    writeln!(writer, "\n{}", "与默认语言相同的未翻译值:".blue().bold())?;
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
+    table.set_titles(row![b => "语言", "翻译键", "文件路径", "值"]);
+
+    for completeness in &result.completeness {
+        for (i, key) in completeness.untranslated_keys.iter().enumerate() {
+            let lang_cell = if i == 0 {
+                Cell::new(&completeness.language).style_spec("b")
+            } else {
+                Cell::new("")
+            };
+
+            table.add_row(Row::new(vec![
+                lang_cell,
+                Cell::new(&key.key),
+                Cell::new(&key.file_path),
+                Cell::new(&key.value.chars().take(50).collect::<String>()),
+            ]));
+        }
+    }
+
+    table.print(writer)?;
+    Ok(())
+}
+
+/// 打印没有使用 t!() 的硬编码字符串及建议的翻译键
+pub fn print_hardcoded_strings(writer: &mut dyn Write, found: &[HardcodedString]) -> Result<()> {
+    if found.is_empty() {
+        writeln!(writer, "{}", "没有发现硬编码字符串".green())?;
+        return Ok(());
+    }
+
+    writeln!(writer, "\n{}", "没有使用 t!() 的硬编码字符串:".bold())?;
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
+    table.set_titles(row![b => "位置", "调用", "文本", "建议的翻译键"]);
+
+    for hardcoded in found {
+        let key = if hardcoded.already_defined {
+            format!("{} (已存在)", hardcoded.suggested_key)
+        } else {
+            hardcoded.suggested_key.clone()
+        };
+        table.add_row(row![
+            format!("{}:{}", hardcoded.file_path, hardcoded.line_number),
+            hardcoded.call,
+            hardcoded.text.chars().take(50).collect::<String>().yellow(),
+            key
+        ]);
+    }
+    table.print(writer)?;
+
+    Ok(())
+}
+
+/// 打印翻译键的所有调用位置和定义
+pub fn print_key_references(
+    writer: &mut dyn Write,
+    key: &str,
+    usages: &[&UsedKey],
+    definitions: &[&DefinedKey],
+) -> Result<()> {
+    writeln!(writer, "\n{} 的调用位置 ({}):", key.bold(), usages.len())?;
+    for usage in usages {
+        let mut location = format!("{}:{}:{}", usage.file_path, usage.line_number, usage.column);
+        if usage.context != UsageContext::Production {
+            location.push_str(&format!(" ({})", usage.context.label()));
+        }
+        writeln!(writer, "  {}", location)?;
+    }
+
+    writeln!(writer, "\n{} 的定义 ({}):", key.bold(), definitions.len())?;
+    if definitions.is_empty() {
+        return Ok(());
+    }
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
+    table.set_titles(row![b => "语言", "文件路径", "值"]);
+    for def_key in definitions {
+        table.add_row(row![def_key.language, def_key.file_path, def_key.value]);
+    }
+    table.print(writer)?;
+
+    Ok(())
+}
+
+/// 并排打印翻译键在所有语言中的值，默认语言排在最前，缺少翻译的语言标记为缺失
+pub fn print_key_values(
+    writer: &mut dyn Write,
+    key: &str,
+    defined_keys: &[DefinedKey],
+    default_locale: &str,
+) -> Result<()> {
+    let mut languages: Vec<&str> = defined_keys.iter()
+        .map(|k| k.language.as_str())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    languages.sort_by_key(|lang| *lang != default_locale);
+
+    writeln!(writer, "\n{}", key.bold())?;
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_DEFAULT);
+    table.set_titles(row![b => "语言", "值"]);
+    for language in languages {
+        match defined_keys.iter().find(|k| k.language == language && k.key == key) {
+            Some(def_key) => table.add_row(row![language, def_key.value]),
+            None => table.add_row(row![language, "(缺失)".red()]),
+        };
+    }
+    table.print(writer)?;
+
     Ok(())
 }
 
 /// 将分析结果以 JSON 格式打印
+/// 打印监视模式中一次更新带来的审计结果变化
+pub fn print_findings_diff(writer: &mut dyn Write, changed_files: &[String], changes: &[FindingChange]) -> Result<()> {
+    writeln!(writer, "\n{} {}", "检测到修改:".bold(), changed_files.join(", "))?;
+    if changes.is_empty() {
+        writeln!(writer, "  审计结果没有变化")?;
+    }
+
+    for change in changes {
+        let line = match change {
+            FindingChange::Missing { key, language } => format!("+ 缺少翻译  {} ({})", key, language).red(),
+            FindingChange::Translated { key, language } => format!("✓ 已翻译    {} ({})", key, language).green(),
+            FindingChange::NoLongerReferenced { key, language } => format!("- 不再引用  {} ({})", key, language).normal(),
+            FindingChange::Unused { key, language } => format!("+ 未使用    {} ({})", key, language).yellow(),
+            FindingChange::NowUsed { key, language } => format!("✓ 已使用    {} ({})", key, language).green(),
+            FindingChange::Removed { key, language } => format!("- 已删除    {} ({})", key, language).normal(),
+        };
+        writeln!(writer, "  {}", line)?;
+    }
+    Ok(())
+}
+
+/// 打印监视模式中的简要统计
+pub fn print_watch_summary(writer: &mut dyn Write, result: &AnalysisResult) -> Result<()> {
+    writeln!(
+        writer,
+        "缺少翻译的键: {}，未使用的翻译键: {} ({:.2}%)",
+        result.total_missing.to_string().red(),
+        result.total_unused.to_string().yellow(),
+        result.unused_percentage
+    )?;
+    Ok(())
+}
+
 pub fn print_json_report(writer: &mut dyn Write, result: &AnalysisResult, output_path: Option<&Path>) -> Result<()> {
     let json_str = serde_json::to_string_pretty(result)?;
     
@@ -175,4 +494,4 @@ pub fn print_yaml_report(
     }
 
     Ok(())
-} 
\ No newline at end of file
+}
@@ -1,12 +1,96 @@
-use anyhow::{Result, Context};
+use anyhow::{Result, Context, bail};
 use log::{info, debug};
 use regex::Regex;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::LazyLock;
 use walkdir::WalkDir;
 
+use crate::cfg::{self, CfgSet};
 use crate::config::Config;
+use crate::symbols::SymbolTable;
+use crate::syntax;
+
+/// 翻译键的解析方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyResolution {
+    /// 宏调用中直接写出的字面量
+    #[default]
+    Literal,
+    /// 通过局部变量 `let var = "key";` 解析
+    Variable,
+    /// 通过 `const` / `static` 字符串常量解析
+    Constant,
+    /// 通过数组常量的下标访问解析，数组中的所有元素都视为已使用
+    ArrayElement,
+    /// 通过方法调用 `t!(expr.method())` 解析，方法可能返回的所有字面量都视为已使用
+    Function,
+    /// 由 `format!` / `concat!` 参数推断出的键模式，例如 `status.*`
+    Pattern,
+    /// `format!` 参数是派生了 strum 字符串转换的枚举，按每个变体展开为具体的键
+    EnumVariant,
+}
+
+/// 翻译键使用位置所处的代码上下文
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageContext {
+    /// 正式代码
+    #[default]
+    Production,
+    /// 测试代码：tests/ 目录、`#[cfg(test)]` 模块和 `#[test]` 函数
+    Test,
+    /// examples/ 目录中的示例
+    Example,
+    /// benches/ 目录中的基准测试
+    Bench,
+    /// 构建脚本 build.rs
+    BuildScript,
+}
+
+impl UsageContext {
+    /// 根据文件相对于项目根目录的路径判断上下文
+    pub fn from_path(relative_path: &str) -> Self {
+        let path = Path::new(relative_path);
+        match path.components().next().and_then(|c| c.as_os_str().to_str()) {
+            Some("tests") => UsageContext::Test,
+            Some("examples") => UsageContext::Example,
+            Some("benches") => UsageContext::Bench,
+            Some("build.rs") => UsageContext::BuildScript,
+            _ => UsageContext::Production,
+        }
+    }
+    
+    /// 上下文的显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            UsageContext::Production => "正式代码",
+            UsageContext::Test => "测试",
+            UsageContext::Example => "示例",
+            UsageContext::Bench => "基准测试",
+            UsageContext::BuildScript => "构建脚本",
+        }
+    }
+}
+
+impl FromStr for UsageContext {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "production" | "prod" => Ok(UsageContext::Production),
+            "test" | "tests" => Ok(UsageContext::Test),
+            "example" | "examples" => Ok(UsageContext::Example),
+            "bench" | "benches" => Ok(UsageContext::Bench),
+            "build" | "build-script" | "build_script" => Ok(UsageContext::BuildScript),
+            _ => bail!("不支持的使用上下文: {}（可选: production, test, example, bench, build）", s),
+        }
+    }
+}
 
 /// 用于表示使用中的翻译键
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,81 +103,354 @@ pub struct UsedKey {
     pub file_path: String,
     /// 所在行号
     pub line_number: usize,
+    /// 所在列号（从 1 开始，按字符计算），指向翻译调用的开头
+    #[serde(default)]
+    pub column: usize,
+    /// 键的解析方式
+    #[serde(default)]
+    pub resolution: KeyResolution,
+    /// 使用位置在当前 cfg 配置下被禁用时，禁用它的 cfg 条件
+    #[serde(default)]
+    pub disabled_by: Option<String>,
+    /// 使用位置所处的代码上下文
+    #[serde(default)]
+    pub context: UsageContext,
+    /// 通过 `locale = "fr"` 参数显式指定的语言
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+/// 模板文件的扩展名
+pub const TEMPLATE_FILE_EXTENSIONS: [&str; 4] = ["html", "jinja", "jinja2", "tera"];
+
+/// 整个 crate 范围的扫描上下文：字符串常量、被禁用的子模块和测试子模块
+///
+/// 单独重新扫描某个文件时复用，不需要再读取其他文件。
+pub struct SourceIndex {
+    symbols: SymbolTable,
+    cfg_set: Option<CfgSet>,
+    disabled_modules: Vec<(String, String)>,
+    test_modules: Vec<String>,
+}
+
+impl SourceIndex {
+    /// 根据所有 Rust 源文件（相对路径和内容）建立扫描上下文
+    pub fn new(config: &Config, sources: &[(String, String)]) -> Self {
+        // 先收集整个 crate 的字符串常量，再解析 t!(CONST) 之类的引用
+        let symbols = SymbolTable::from_sources(
+            sources.iter().map(|(path, content)| (path.as_str(), content.as_str()))
+        );
+        
+        // 根据 feature 和 cfg 选项找出被禁用的子模块
+        let cfg_set = CfgSet::from_config(config);
+        let disabled_modules = cfg_set.as_ref()
+            .map(|cfg_set| sources.iter()
+                .flat_map(|(path, content)| cfg::disabled_module_dirs(path, content, cfg_set))
+                .collect())
+            .unwrap_or_default();
+        
+        // #[cfg(test)] mod tests; 声明的测试子模块
+        let test_modules = sources.iter()
+            .flat_map(|(path, content)| cfg::test_module_dirs(path, content))
+            .collect();
+        
+        SourceIndex { symbols, cfg_set, disabled_modules, test_modules }
+    }
 }
 
-/// 扫描源代码，提取所有 t!() 宏调用中使用的键
+/// 扫描源代码，提取每一处 t!() 宏调用中使用的键
+///
+/// 同一个键的每个调用位置都会保留，由分析阶段统计使用次数。
 pub fn scan_source_code(config: &Config) -> Result<Vec<UsedKey>> {
-    let src_path = config.src_path();
-    info!("正在扫描源代码目录: {}", src_path.display());
+    let sources = read_rust_sources(config)?;
+    let index = SourceIndex::new(config, &sources);
     
     let mut used_keys = Vec::new();
+    for (relative_path, content) in &sources {
+        used_keys.extend(scan_rust_file(relative_path, content, &index)?);
+    }
+    
+    // 扫描模板文件中的翻译调用
+    for (relative_path, content) in read_template_sources(config)? {
+        used_keys.extend(scan_template_file(&relative_path, &content)?);
+    }
+
+    info!("扫描完成，找到 {} 处翻译键使用", used_keys.len());
+    Ok(used_keys)
+}
+
+/// 需要扫描的 Rust 源文件所在的目录和文件
+///
+/// 除源代码目录外，还包括测试、示例、基准测试和构建脚本。
+pub fn source_roots(config: &Config) -> Vec<PathBuf> {
+    vec![
+        config.src_path(),
+        config.project_path.join("tests"),
+        config.project_path.join("examples"),
+        config.project_path.join("benches"),
+        config.project_path.join("build.rs"),
+    ]
+}
+
+/// 需要扫描的所有 Rust 源文件
+pub fn rust_source_files(config: &Config) -> Vec<PathBuf> {
     let rust_file_extensions = ["rs"];
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
     
-    for entry in WalkDir::new(&src_path)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok()) 
-    {
-        let path = entry.path();
-        
-        // 只处理 Rust 文件
-        if path.is_file() && path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| rust_file_extensions.contains(&ext))
-            .unwrap_or(false)
+    for root in source_roots(config).iter().filter(|root| root.exists()) {
+        for entry in WalkDir::new(root)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|e| e.ok()) 
         {
-            debug!("处理文件: {}", path.display());
+            let path = entry.path();
             
-            // 读取文件内容
-            let relative_path = path.strip_prefix(&config.project_path)
-                .unwrap_or(path)
-                .to_string_lossy()
-                .to_string();
-                
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("无法读取文件: {}", path.display()))?;
-                
-            // 扫描文件内容中的 t!() 宏调用
-            scan_file_content(&content, &relative_path, &mut used_keys)?;
+            // 只处理 Rust 文件
+            if path.is_file() && path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| rust_file_extensions.contains(&ext))
+                .unwrap_or(false)
+                && seen.insert(path.to_path_buf())
+            {
+                files.push(path.to_path_buf());
+            }
+        }
+    }
+    
+    files
+}
+
+/// 模板目录中的所有模板文件
+pub fn template_files(config: &Config) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    
+    for template_path in config.template_paths() {
+        for entry in WalkDir::new(&template_path)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if path.is_file() && path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| TEMPLATE_FILE_EXTENSIONS.contains(&ext))
+                .unwrap_or(false)
+            {
+                files.push(path.to_path_buf());
+            }
         }
     }
     
-    // 去重
-    let mut unique_keys: HashMap<String, UsedKey> = HashMap::new();
-    for key in used_keys {
-        unique_keys.entry(key.key.clone())
-            .or_insert_with(|| key);
+    files
+}
+
+/// 读取需要扫描的所有 Rust 源文件，返回相对于项目根目录的路径和内容
+pub fn read_rust_sources(config: &Config) -> Result<Vec<(String, String)>> {
+    info!("正在扫描源代码目录: {}", config.src_path().display());
+    
+    rust_source_files(config).iter()
+        .map(|path| {
+            debug!("处理文件: {}", path.display());
+            read_source(config, path)
+        })
+        .collect()
+}
+
+/// 读取模板目录中的所有模板文件，返回相对于项目根目录的路径和内容
+pub fn read_template_sources(config: &Config) -> Result<Vec<(String, String)>> {
+    for template_path in config.template_paths() {
+        info!("正在扫描模板目录: {}", template_path.display());
+    }
+    
+    template_files(config).iter()
+        .map(|path| {
+            debug!("处理模板文件: {}", path.display());
+            read_source(config, path)
+        })
+        .collect()
+}
+
+/// 读取文件内容，返回相对于项目根目录的路径和内容
+fn read_source(config: &Config, path: &Path) -> Result<(String, String)> {
+    let relative_path = path.strip_prefix(&config.project_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string();
+    
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("无法读取文件: {}", path.display()))?;
+    
+    Ok((relative_path, content))
+}
+
+/// 扫描一个 Rust 源文件，并标记每处使用所处的上下文和 cfg 条件
+pub fn scan_rust_file(relative_path: &str, content: &str, index: &SourceIndex) -> Result<Vec<UsedKey>> {
+    // 扫描文件内容中的 t!() 宏调用
+    let mut used_keys = Vec::new();
+    scan_file_content(content, relative_path, &index.symbols, &mut used_keys)?;
+    
+    // 标记使用位置所处的上下文
+    let is_test_module = index.test_modules.iter().any(|dir| {
+        *relative_path == format!("{}.rs", dir) || Path::new(relative_path).starts_with(dir)
+    });
+    let file_context = if is_test_module { UsageContext::Test } else { UsageContext::from_path(relative_path) };
+    let test_regions = cfg::test_regions(content);
+    for used_key in &mut used_keys {
+        let in_test_region = test_regions.iter()
+            .any(|(start, end)| *start <= used_key.line_number && used_key.line_number <= *end);
+        used_key.context = if in_test_region { UsageContext::Test } else { file_context };
     }
     
-    let result: Vec<UsedKey> = unique_keys.into_values().collect();
-    info!("扫描完成，找到 {} 个使用中的翻译键", result.len());
+    // 标记处于禁用配置中的使用位置
+    if let Some(cfg_set) = &index.cfg_set {
+        let module_condition = index.disabled_modules.iter()
+            .find(|(dir, _)| {
+                let module_file = format!("{}.rs", dir);
+                *relative_path == module_file || Path::new(relative_path).starts_with(dir)
+            })
+            .map(|(_, condition)| condition.clone());
+        let regions = cfg::disabled_regions(content, cfg_set);
+        
+        for used_key in &mut used_keys {
+            used_key.disabled_by = module_condition.clone().or_else(|| {
+                regions.iter()
+                    .find(|r| r.start_line <= used_key.line_number && used_key.line_number <= r.end_line)
+                    .map(|r| r.condition.clone())
+            });
+        }
+    }
     
-    Ok(result)
+    Ok(used_keys)
+}
+
+/// 扫描一个模板文件中的翻译调用
+pub fn scan_template_file(relative_path: &str, content: &str) -> Result<Vec<UsedKey>> {
+    let mut used_keys = Vec::new();
+    scan_template_content(content, relative_path, &mut used_keys)?;
+    Ok(used_keys)
 }
 
+/// 带命名空间的字面量键: rust_i18n::t!("key")
+static NAMESPACED_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"rust_i18n::t!\s*\(\s*"([^"]+)"\s*[,)]"#).unwrap()
+});
+
+/// 命名空间动态键: rust_i18n::t!(var)、rust_i18n::t!(module::CONST)、rust_i18n::t!(ARRAY[i])
+static NS_VAR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"rust_i18n::t!\s*\(\s*&?\s*((?:[a-zA-Z_][a-zA-Z0-9_]*::)*[a-zA-Z_][a-zA-Z0-9_]*)\s*(\[[^\]]*\])?\s*(?:,[^)]*)?\)"#).unwrap()
+});
+
+/// 标准字面量键: t!("key")
+static STANDARD_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bt!\s*\(\s*"([^"]+)"\s*[,)]"#).unwrap()
+});
+
+/// 标准动态键: t!(var)、t!(module::CONST)、t!(ARRAY[i])
+static STD_VAR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bt!\s*\(\s*&?\s*((?:[a-zA-Z_][a-zA-Z0-9_]*::)*[a-zA-Z_][a-zA-Z0-9_]*)\s*(\[[^\]]*\])?\s*(?:,[^)]*)?\)"#).unwrap()
+});
+
+/// 宏构造的键: t!(&format!(...)) 或 t!(concat!(...))
+static MACRO_KEY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bt!\s*\(\s*&?\s*(format|concat)!\s*\("#).unwrap()
+});
+
+/// leptos_i18n 键路径: t!(i18n, home.title)、t_string!(i18n, home.title)、td!(locale, home.title)
+static LEPTOS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\btd?(?:_string|_display)?!\s*\(\s*[a-zA-Z_][a-zA-Z0-9_]*\s*,\s*([a-zA-Z_][a-zA-Z0-9_]*(?:\.[a-zA-Z_][a-zA-Z0-9_]*)*)\s*[,)]"#).unwrap()
+});
+
+/// 方法调用键: t!(expr.method())
+static METHOD_CALL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bt!\s*\(\s*&?\s*(?:[a-zA-Z_][a-zA-Z0-9_]*(?:\(\s*\))?\s*\.\s*)+([a-zA-Z_][a-zA-Z0-9_]*)\s*\(\s*\)\s*(?:,[^)]*)?\)"#).unwrap()
+});
+
+/// Fluent 宏: fl!(LOADER, "message-id", ...) 或 fl!("message-id")
+static FLUENT_MACRO_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bfl!\s*\(\s*(?:[a-zA-Z_][a-zA-Z0-9_:]*(?:\(\s*\))?\s*,\s*)?"([^"]+)""#).unwrap()
+});
+
+/// Fluent 加载器方法调用: loader.get("message-id")、loader.get_args("message-id", args)、loader.get_attr("message-id", "attr")
+static FLUENT_LOADER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([a-zA-Z_][a-zA-Z0-9_]*)\s*(?:\(\s*\))?\s*\.\s*get(_attr|_args|_args_concrete|_args_fluent|_attr_args)?\s*\(\s*"([^"]+)"(?:\s*,\s*"([^"]+)")?"#).unwrap()
+});
+
 /// 扫描文件内容，提取所有 t!() 宏调用
-fn scan_file_content(content: &str, file_path: &str, used_keys: &mut Vec<UsedKey>) -> Result<()> {
+fn scan_file_content(
+    content: &str,
+    file_path: &str,
+    symbols: &SymbolTable,
+    used_keys: &mut Vec<UsedKey>,
+) -> Result<()> {
     // 使用正则表达式匹配 t!() 宏调用
     // 这里我们考虑以下几种形式：
     // 1. t!("literal.key")
     // 2. t!("literal.key", param = "value")
     // 3. t!(format!("dynamic.key.{}", var))
-    // 4. t!(dynamic_key_var)
+    // 4. t!(dynamic_key_var) / t!(CONST) / t!(module::CONST) / t!(ARRAY[i])
     // 5. rust_i18n::t!("literal.key")
+    // 5.1 t!(err.key()) / rust_i18n::t!(self.kind.key())
+    // 5.2 t!(&format!("status.{}", state)) / t!(concat!("menu.", "file"))
+    // 5.3 t!(i18n, home.title) / td!(locale, home.title)（leptos_i18n）
+    // 5.4 view! / rsx! 中跨多行的 t!() 调用，以及字符串中转义的 t!(\"key\")
+    // 6. fl!(LOADER, "message-id") / fl!("message-id")（Fluent）
+    // 7. loader.get("message-id") / loader.get_attr("message-id", "attr")（Fluent）
     
-    // 遍历每一行查找宏调用
-    for (line_idx, line) in content.lines().enumerate() {
-        // 排除 format! 宏
-        if line.contains("format!") {
+    // 遍历每一行查找宏调用，跨多行的宏调用合并到起始行；注释和字符串中的调用不计入
+    let lines = join_multiline_macro_calls(&syntax::code_only(content));
+    for (line_idx, line) in lines.iter().enumerate() {
+        // rsx! 等宏的字符串中可能出现转义的调用，例如 "{t!(\"key\")}"
+        let line = if line.contains("!(\\\"") {
+            line.replace("\\\"", "\"")
+        } else {
+            line.clone()
+        };
+        let line = line.as_str();
+        
+        // 处理 format! / concat! 构造的键，转换为键模式
+        for cap in MACRO_KEY_REGEX.captures_iter(line) {
+            let open = cap.get(0).unwrap().end() - 1;
+            let Some(close) = syntax::find_matching_delimiter(line, open) else {
+                continue;
+            };
+            let args = &line[open + 1..close];
+            
+            let (keys, resolution) = if &cap[1] == "format" {
+                let context = content.lines().take(line_idx + 1).collect::<Vec<_>>().join("\n");
+                format_key_candidates(args, &context, file_path, symbols)
+            } else {
+                concat_key(args)
+            };
+            
+            for key in keys.into_iter().filter(|k| !k.is_empty()) {
+                debug!("在 {}:{} 找到 {}! 构造的键: {}", file_path, line_idx + 1, &cap[1], key);
+                
+                used_keys.push(UsedKey {
+                    key,
+                    is_literal: resolution != KeyResolution::Pattern,
+                    file_path: file_path.to_string(),
+                    line_number: line_idx + 1,
+                    column: column_of(line, cap.get(0).unwrap().start()),
+                    resolution,
+                    disabled_by: None,
+                    context: UsageContext::Production,
+                    locale: None,
+                });
+            }
+        }
+        
+        // 排除 format! / concat! 宏，避免把其中的字符串误认为 t!() 调用
+        if line.contains("format!") || line.contains("concat!") {
             continue;
         }
 
         // 处理命名空间的字面量键
         if line.contains("rust_i18n::t!") {
-            // 处理带命名空间的字面量键: rust_i18n::t!("key") 
-            let namespaced_regex = Regex::new(r#"rust_i18n::t!\s*\(\s*"([^"]+)"(?:\s*(?:,|\)))(?:[^)]*\))?"#)?;
-            for cap in namespaced_regex.captures_iter(line) {
+            // 处理带命名空间的字面量键: rust_i18n::t!("key")
+            for cap in NAMESPACED_REGEX.captures_iter(line) {
                 if let Some(key_match) = cap.get(1) {
+                    let locale = explicit_locale(line, cap.get(0).unwrap().start());
                     let key = key_match.as_str().to_string();
                     debug!("在 {}:{} 找到命名空间字面量键: {}", file_path, line_idx + 1, key);
                     
@@ -102,25 +459,32 @@ fn scan_file_content(content: &str, file_path: &str, used_keys: &mut Vec<UsedKey
                         is_literal: true,
                         file_path: file_path.to_string(),
                         line_number: line_idx + 1,
+                        column: column_of(line, cap.get(0).unwrap().start()),
+                        resolution: KeyResolution::Literal,
+                        disabled_by: None,
+                        context: UsageContext::Production,
+                        locale,
                     });
                 }
             }
 
             // 处理命名空间动态键
-            let ns_var_regex = Regex::new(r#"rust_i18n::t!\s*\(\s*([a-zA-Z0-9_]+)\s*\)"#)?;
-            for cap in ns_var_regex.captures_iter(line) {
+            for cap in NS_VAR_REGEX.captures_iter(line) {
                 if let Some(var_match) = cap.get(1) {
                     let var_name = var_match.as_str();
                     debug!("在 {}:{} 找到命名空间变量键引用: {}", file_path, line_idx + 1, var_name);
                     
-                    process_dynamic_key(var_name, content, line_idx, file_path, used_keys)?;
+                    let is_indexed = cap.get(2).is_some();
+                    
+                    let column = column_of(line, cap.get(0).unwrap().start());
+                    process_dynamic_key(var_name, is_indexed, content, line_idx, column, file_path, symbols, used_keys)?;
                 }
             }
         } else {
             // 处理标准字面量键: t!("key")
-            let standard_regex = Regex::new(r#"t!\s*\(\s*"([^"]+)"(?:\s*(?:,|\)))(?:[^)]*\))?"#)?;
-            for cap in standard_regex.captures_iter(line) {
+            for cap in STANDARD_REGEX.captures_iter(line) {
                 if let Some(key_match) = cap.get(1) {
+                    let locale = explicit_locale(line, cap.get(0).unwrap().start());
                     let key = key_match.as_str().to_string();
                     debug!("在 {}:{} 找到标准字面量键: {}", file_path, line_idx + 1, key);
                     
@@ -129,61 +493,469 @@ fn scan_file_content(content: &str, file_path: &str, used_keys: &mut Vec<UsedKey
                         is_literal: true,
                         file_path: file_path.to_string(),
                         line_number: line_idx + 1,
+                        column: column_of(line, cap.get(0).unwrap().start()),
+                        resolution: KeyResolution::Literal,
+                        disabled_by: None,
+                        context: UsageContext::Production,
+                        locale,
                     });
                 }
             }
 
             // 处理标准动态键
-            let std_var_regex = Regex::new(r#"t!\s*\(\s*([a-zA-Z0-9_]+)\s*\)"#)?;
-            for cap in std_var_regex.captures_iter(line) {
+            for cap in STD_VAR_REGEX.captures_iter(line) {
                 if let Some(var_match) = cap.get(1) {
                     let var_name = var_match.as_str();
                     debug!("在 {}:{} 找到标准变量键引用: {}", file_path, line_idx + 1, var_name);
                     
-                    process_dynamic_key(var_name, content, line_idx, file_path, used_keys)?;
+                    let is_indexed = cap.get(2).is_some();
+                    
+                    let column = column_of(line, cap.get(0).unwrap().start());
+                    process_dynamic_key(var_name, is_indexed, content, line_idx, column, file_path, symbols, used_keys)?;
+                }
+            }
+        }
+        
+        // 处理 leptos_i18n 的键路径: t!(i18n, home.title)
+        for cap in LEPTOS_REGEX.captures_iter(line) {
+            let key = cap[1].to_string();
+            debug!("在 {}:{} 找到 leptos_i18n 键: {}", file_path, line_idx + 1, key);
+            
+            used_keys.push(UsedKey {
+                key,
+                is_literal: true,
+                file_path: file_path.to_string(),
+                line_number: line_idx + 1,
+                column: column_of(line, cap.get(0).unwrap().start()),
+                resolution: KeyResolution::Literal,
+                disabled_by: None,
+                context: UsageContext::Production,
+                locale: None,
+            });
+        }
+        
+        // 处理方法调用键: t!(err.key())，收集方法可能返回的所有字符串字面量
+        for cap in METHOD_CALL_REGEX.captures_iter(line) {
+            let method_name = &cap[1];
+            debug!("在 {}:{} 找到方法调用键: {}()", file_path, line_idx + 1, method_name);
+            
+            let methods = symbols.resolve_method(method_name, file_path);
+            if methods.is_empty() {
+                debug!("  未找到返回字符串的方法 {}", method_name);
+            }
+            
+            for symbol in methods {
+                debug!("  找到方法定义: {} -> {:?} ({})", method_name, symbol.values, symbol.file_path);
+                
+                for value in &symbol.values {
+                    used_keys.push(UsedKey {
+                        key: value.clone(),
+                        is_literal: true,
+                        file_path: file_path.to_string(),
+                        line_number: line_idx + 1,
+                        column: column_of(line, cap.get(0).unwrap().start()),
+                        resolution: KeyResolution::Function,
+                        disabled_by: None,
+                        context: UsageContext::Production,
+                        locale: None,
+                    });
                 }
             }
         }
+        
+        // 处理 Fluent 宏调用: fl!(LOADER, "message-id")
+        for cap in FLUENT_MACRO_REGEX.captures_iter(line) {
+            let key = cap[1].to_string();
+            debug!("在 {}:{} 找到 Fluent 消息键: {}", file_path, line_idx + 1, key);
+            
+            used_keys.push(UsedKey {
+                key,
+                is_literal: true,
+                file_path: file_path.to_string(),
+                line_number: line_idx + 1,
+                column: column_of(line, cap.get(0).unwrap().start()),
+                resolution: KeyResolution::Literal,
+                disabled_by: None,
+                context: UsageContext::Production,
+                locale: None,
+            });
+        }
+        
+        // 处理 Fluent 加载器方法调用，仅识别名称中包含 loader 的接收者，避免误判普通的 get() 调用
+        for cap in FLUENT_LOADER_REGEX.captures_iter(line) {
+            if !cap[1].to_lowercase().contains("loader") {
+                continue;
+            }
+            
+            let is_attr = cap.get(2).is_some_and(|m| m.as_str().starts_with("_attr"));
+            let key = match (is_attr, cap.get(4)) {
+                (true, Some(attr)) => format!("{}.{}", &cap[3], attr.as_str()),
+                _ => cap[3].to_string(),
+            };
+            debug!("在 {}:{} 找到 Fluent 加载器键: {}", file_path, line_idx + 1, key);
+            
+            used_keys.push(UsedKey {
+                key,
+                is_literal: true,
+                file_path: file_path.to_string(),
+                line_number: line_idx + 1,
+                column: column_of(line, cap.get(0).unwrap().start()),
+                resolution: KeyResolution::Literal,
+                disabled_by: None,
+                context: UsageContext::Production,
+                locale: None,
+            });
+        }
+    }
+    
+    Ok(())
+}
+
+/// 把行内的字节偏移转换为从 1 开始的字符列号
+fn column_of(line: &str, byte_offset: usize) -> usize {
+    line[..byte_offset].chars().count() + 1
+}
+
+/// 翻译调用中的 `locale = "fr"` 参数
+static LOCALE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\blocale\s*=\s*"([^"]+)""#).unwrap());
+
+/// 读取从 `call_start` 开始的 t!() 调用中字面量形式的 `locale = "fr"` 参数
+fn explicit_locale(line: &str, call_start: usize) -> Option<String> {
+    let open = call_start + line[call_start..].find('(')?;
+    let close = syntax::find_matching_delimiter(line, open).unwrap_or(line.len());
+    LOCALE_REGEX.captures(&line[open..close]).map(|caps| caps[1].to_string())
+}
+
+/// 翻译宏调用的开头，用于合并跨多行的调用
+static TRANSLATION_MACRO_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:t|td|t_string|td_string|t_display|td_display|fl)!\s*\(").unwrap()
+});
+
+/// 把跨多行的翻译宏调用（常见于 view! / rsx! 等 UI 宏中）合并到起始行，后续行置空以保持行号不变
+fn join_multiline_macro_calls(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    
+    let mut covered_until = 0;
+    for m in TRANSLATION_MACRO_REGEX.find_iter(content) {
+        if m.start() < covered_until {
+            continue;
+        }
+        let open = m.end() - 1;
+        let Some(close) = syntax::find_matching_delimiter(content, open) else {
+            continue;
+        };
+        covered_until = close;
+        
+        let start_line = content[..m.start()].matches('\n').count();
+        let end_line = start_line + content[m.start()..close].matches('\n').count();
+        if start_line == end_line || end_line >= lines.len() {
+            continue;
+        }
+        
+        let joined = lines[start_line..=end_line].iter()
+            .map(|l| l.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+        lines[start_line] = joined;
+        for line in &mut lines[start_line + 1..=end_line] {
+            line.clear();
+        }
+    }
+    
+    lines
+}
+
+/// 模板中的翻译函数调用: t("key")、t(key="key")、t!("key")
+static TEMPLATE_CALL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bt!?\s*\(\s*(?:key\s*=\s*)?(?:"([^"]+)"|'([^']+)')"#).unwrap()
+});
+
+/// 模板中的翻译过滤器: "key" | t
+static TEMPLATE_FILTER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:"([^"]+)"|'([^']+)')\s*\|\s*t\b"#).unwrap()
+});
+
+/// 扫描模板文件（askama、tera 等）中的翻译调用
+///
+/// 支持 `{{ t("key") }}`、`{{ t(key="key") }}`、`{{ t!("key") }}` 以及过滤器形式 `{{ "key" | t }}`。
+fn scan_template_content(content: &str, file_path: &str, used_keys: &mut Vec<UsedKey>) -> Result<()> {
+    for (line_idx, line) in content.lines().enumerate() {
+        for cap in TEMPLATE_CALL_REGEX.captures_iter(line).chain(TEMPLATE_FILTER_REGEX.captures_iter(line)) {
+            let Some(key) = cap.get(1).or_else(|| cap.get(2)) else {
+                continue;
+            };
+            debug!("在 {}:{} 找到模板中的键: {}", file_path, line_idx + 1, key.as_str());
+            
+            used_keys.push(UsedKey {
+                key: key.as_str().to_string(),
+                is_literal: true,
+                file_path: file_path.to_string(),
+                line_number: line_idx + 1,
+                column: column_of(line, cap.get(0).unwrap().start()),
+                resolution: KeyResolution::Literal,
+                disabled_by: None,
+                context: UsageContext::Production,
+                locale: None,
+            });
+        }
     }
     
     Ok(())
 }
 
-/// 处理动态键变量，查找变量定义并添加到使用键列表中
+/// 枚举展开后最多生成的键数量，超过时退回键模式
+const MAX_EXPANDED_KEYS: usize = 256;
+
+/// format! 的命名参数: name = expr
+static NAMED_ARGUMENT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([a-zA-Z_][a-zA-Z0-9_]*)\s*=([^=].*)$").unwrap()
+});
+
+/// 可能是枚举值的 format! 参数: role、&role、self.role、role.as_ref()、role.to_string()
+static ENUM_EXPR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^&?\s*(?:[a-zA-Z_][a-zA-Z0-9_]*\s*\.\s*)*([a-zA-Z_][a-zA-Z0-9_]*)(?:\s*\.\s*(?:as_ref|to_string|into)\s*\(\s*\))?$"
+    ).unwrap()
+});
+
+/// 变量的类型标注: role: Role、role: &models::Role
+static TYPE_ANNOTATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b([a-zA-Z_][a-zA-Z0-9_]*)\s*:\s*&?\s*(?:'[a-zA-Z_]+\s+)?(?:mut\s+)?((?:[a-zA-Z_][a-zA-Z0-9_]*::)*[A-Z][a-zA-Z0-9_]*)"
+    ).unwrap()
+});
+
+/// 以枚举变体赋值的变量: let role = Role::Admin
+static ENUM_ASSIGNMENT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\blet\s+(?:mut\s+)?([a-zA-Z_][a-zA-Z0-9_]*)\s*=\s*((?:[a-zA-Z_][a-zA-Z0-9_]*::)*[A-Z][a-zA-Z0-9_]*)::[A-Z][a-zA-Z0-9_]*"
+    ).unwrap()
+});
+
+/// 根据 concat! 的参数推断键：所有参数都是字符串字面量时就是具体的键，否则非字面量的参数替换为 `*`
+fn concat_key(args: &str) -> (Vec<String>, KeyResolution) {
+    let mut is_exact = true;
+    let key: String = syntax::split_top_level(args, b',').into_iter()
+        .map(str::trim)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| match syntax::string_literal_spans(arg).as_slice() {
+            [(span, value)] if span.len() == arg.len() => value.clone(),
+            _ => {
+                is_exact = false;
+                "*".to_string()
+            }
+        })
+        .collect();
+    (vec![key], if is_exact { KeyResolution::Literal } else { KeyResolution::Pattern })
+}
+
+/// 根据 format! 的参数推断可能的键
+///
+/// 没有占位符时格式字符串就是具体的键；参数是派生了 strum 字符串转换的枚举时，按每个变体展开，
+/// 所有占位符都能展开时返回具体的键，否则无法展开的占位符替换为 `*`，返回键模式。
+fn format_key_candidates(
+    args: &str,
+    context: &str,
+    file_path: &str,
+    symbols: &SymbolTable,
+) -> (Vec<String>, KeyResolution) {
+    let mut args = syntax::split_top_level(args, b',').into_iter().map(str::trim);
+    let Some(fmt) = args.next().and_then(|fmt| syntax::string_literals(fmt).into_iter().next()) else {
+        return (Vec::new(), KeyResolution::Pattern);
+    };
+    let (pieces, placeholders) = split_format_string(&fmt);
+    if placeholders.is_empty() {
+        return (pieces, KeyResolution::Literal);
+    }
+    
+    // 区分位置参数和命名参数 name = expr
+    let mut positional = Vec::new();
+    let mut named = HashMap::new();
+    for arg in args.filter(|a| !a.is_empty()) {
+        match NAMED_ARGUMENT_REGEX.captures(arg) {
+            Some(caps) => {
+                named.insert(caps[1].to_string(), caps[2].trim().to_string());
+            }
+            None => positional.push(arg.to_string()),
+        }
+    }
+    
+    let mut next_positional = 0;
+    let mut options = Vec::new();
+    let mut is_exact = true;
+    for placeholder in &placeholders {
+        let argument = if placeholder.is_empty() {
+            next_positional += 1;
+            positional.get(next_positional - 1).cloned()
+        } else if let Ok(index) = placeholder.parse::<usize>() {
+            positional.get(index).cloned()
+        } else {
+            // 命名参数或内联捕获的变量 {name}
+            Some(named.get(placeholder).cloned().unwrap_or_else(|| placeholder.clone()))
+        };
+        
+        match argument.and_then(|arg| enum_variants_of(&arg, context, file_path, symbols)) {
+            Some(variants) => options.push(variants),
+            None => {
+                is_exact = false;
+                options.push(vec!["*".to_string()]);
+            }
+        }
+    }
+    
+    let combinations: usize = options.iter().map(Vec::len).product();
+    if combinations > MAX_EXPANDED_KEYS {
+        debug!("  枚举展开后的键数量 {} 超过上限，使用键模式", combinations);
+        return (vec![pieces.join("*")], KeyResolution::Pattern);
+    }
+    
+    let mut keys = vec![pieces[0].clone()];
+    for (values, piece) in options.iter().zip(&pieces[1..]) {
+        keys = keys.iter()
+            .flat_map(|prefix| values.iter().map(move |value| format!("{}{}{}", prefix, value, piece)))
+            .collect();
+    }
+    
+    (keys, if is_exact { KeyResolution::EnumVariant } else { KeyResolution::Pattern })
+}
+
+/// 查找 format! 参数表达式对应的枚举类型，返回所有变体的字符串形式
+///
+/// 支持 `role`、`&role`、`self.role`、`role.as_ref()`、`role.to_string()` 等写法，
+/// 变量类型来自之前的 `role: Role` 类型标注或 `let role = Role::Admin` 赋值。
+fn enum_variants_of(expr: &str, context: &str, file_path: &str, symbols: &SymbolTable) -> Option<Vec<String>> {
+    let var_name = ENUM_EXPR_REGEX.captures(expr.trim())?.get(1)?.as_str();
+    
+    // 取使用位置之前最近的一处类型信息
+    let type_path = TYPE_ANNOTATION_REGEX.captures_iter(context)
+        .chain(ENUM_ASSIGNMENT_REGEX.captures_iter(context))
+        .filter(|caps| &caps[1] == var_name)
+        .max_by_key(|caps| caps.get(0).unwrap().start())
+        .map(|caps| caps[2].to_string())?;
+    
+    let variants: Vec<String> = symbols.resolve_enum(&type_path, file_path)
+        .into_iter()
+        .flat_map(|symbol| symbol.values.iter().cloned())
+        .collect();
+    
+    if variants.is_empty() {
+        return None;
+    }
+    debug!("  参数 {} 是枚举 {}，展开为 {:?}", expr, type_path, variants);
+    Some(variants)
+}
+
+/// 拆分 format! 的格式字符串，返回占位符之间的文本和每个占位符的参数部分（`:` 之前的内容）
+///
+/// 例如 `status.{}.{name:?}` 拆分为 `["status.", ".", ""]` 和 `["", "name"]`。
+fn split_format_string(fmt: &str) -> (Vec<String>, Vec<String>) {
+    let mut pieces = vec![String::new()];
+    let mut placeholders = Vec::new();
+    let mut chars = fmt.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                pieces.last_mut().unwrap().push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                pieces.last_mut().unwrap().push('}');
+            }
+            '{' => {
+                // 读取占位符内容，例如 {}、{0}、{name}、{:?}
+                let mut spec = String::new();
+                for inner in chars.by_ref() {
+                    if inner == '}' {
+                        break;
+                    }
+                    spec.push(inner);
+                }
+                let argument = spec.split(':').next().unwrap_or_default().trim().to_string();
+                placeholders.push(argument);
+                pieces.push(String::new());
+            }
+            _ => pieces.last_mut().unwrap().push(c),
+        }
+    }
+    (pieces, placeholders)
+}
+
+/// 处理动态键变量，查找变量定义或字符串常量并添加到使用键列表中
+#[allow(clippy::too_many_arguments, clippy::implicit_saturating_sub, clippy::collapsible_if)]
 fn process_dynamic_key(
     var_name: &str,
+    is_indexed: bool,
     content: &str,
     line_idx: usize,
+    column: usize,
     file_path: &str,
+    symbols: &SymbolTable,
     used_keys: &mut Vec<UsedKey>
 ) -> Result<()> {
-    // 向上查找变量声明
-    let context_start = if line_idx >= 20 { line_idx - 20 } else { 0 };
-    let context_lines: Vec<&str> = content.lines().collect();
-    let context = &context_lines[context_start..line_idx];
-    
-    // 查找变量声明 let var_name = "key";
-    for ctx_line in context.iter().rev() {
-        let var_decl_regex = Regex::new(&format!(r#"let\s+{}\s*=\s*"([^"]+)";"#, var_name))?;
-        if let Some(decl_cap) = var_decl_regex.captures(ctx_line) {
-            if let Some(key_value) = decl_cap.get(1) {
-                debug!("  找到变量定义: {} = \"{}\"", var_name, key_value.as_str());
-                
-                used_keys.push(UsedKey {
-                    key: key_value.as_str().to_string(),
-                    is_literal: false,
-                    file_path: file_path.to_string(),
-                    line_number: line_idx + 1,
-                });
-                
-                // 找到一个匹配就返回
-                return Ok(());
+    // 局部变量只可能是不带路径的标识符
+    if !var_name.contains("::") && !is_indexed {
+        // 向上查找变量声明
+        let context_start = if line_idx >= 20 { line_idx - 20 } else { 0 };
+        let context_lines: Vec<&str> = content.lines().collect();
+        let context = &context_lines[context_start..line_idx];
+        
+        // 查找变量声明 let var_name = "key";
+        for ctx_line in context.iter().rev() {
+            let var_decl_regex = Regex::new(&format!(r#"let\s+{}\s*=\s*"([^"]+)";"#, var_name))?;
+            if let Some(decl_cap) = var_decl_regex.captures(ctx_line) {
+                if let Some(key_value) = decl_cap.get(1) {
+                    debug!("  找到变量定义: {} = \"{}\"", var_name, key_value.as_str());
+                    
+                    used_keys.push(UsedKey {
+                        key: key_value.as_str().to_string(),
+                        is_literal: false,
+                        file_path: file_path.to_string(),
+                        line_number: line_idx + 1,
+                        column,
+                        resolution: KeyResolution::Variable,
+                        disabled_by: None,
+                        context: UsageContext::Production,
+                        locale: None,
+                    });
+                    
+                    // 找到一个匹配就返回
+                    return Ok(());
+                }
             }
         }
     }
     
-    // 没找到变量定义，添加默认键
-    debug!("  未找到 {} 的变量定义", var_name);
+    // 查找 crate 范围内的字符串常量
+    let symbols_found = symbols.resolve(var_name, file_path);
+    if symbols_found.is_empty() {
+        debug!("  未找到 {} 的变量或常量定义", var_name);
+        return Ok(());
+    }
+    
+    for symbol in symbols_found {
+        // 数组常量无法静态确定下标，所有元素都视为已使用
+        let resolution = if symbol.is_array {
+            KeyResolution::ArrayElement
+        } else {
+            KeyResolution::Constant
+        };
+        debug!("  找到常量定义: {} = {:?} ({})", var_name, symbol.values, symbol.file_path);
+        
+        for value in &symbol.values {
+            used_keys.push(UsedKey {
+                key: value.clone(),
+                is_literal: true,
+                file_path: file_path.to_string(),
+                line_number: line_idx + 1,
+                column,
+                resolution,
+                disabled_by: None,
+                context: UsageContext::Production,
+                locale: None,
+            });
+        }
+    }
     
     Ok(())
 }
@@ -202,13 +974,48 @@ mod tests {
         "#;
         let mut used_keys = Vec::new();
         
-        scan_file_content(content, "test.rs", &mut used_keys).unwrap();
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
         
         assert_eq!(used_keys.len(), 2);
         assert_eq!(used_keys[0].key, "greetings.hello");
         assert_eq!(used_keys[1].key, "greetings.goodbye");
     }
 
+    #[test]
+    fn test_scan_file_content_columns() {
+        let content = r#"
+        fn main() {
+            println!("测试: {}", t!("greetings.hello"));
+            let key = "dynamic.key";
+            println!("{}", rust_i18n::t!(key));
+        }
+        "#;
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
+        
+        // 列号按字符计算，指向翻译调用的开头
+        let columns: Vec<(&str, usize)> = used_keys.iter().map(|k| (k.key.as_str(), k.column)).collect();
+        assert_eq!(columns, vec![("greetings.hello", 32), ("dynamic.key", 28)]);
+    }
+
+    #[test]
+    fn test_scan_file_content_requires_word_boundary() {
+        let content = r#"
+        fn main() {
+            print!("not a key");
+            eprint!(message);
+            println!("{}", t!("greetings.hello"));
+        }
+        "#;
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
+        
+        let keys: Vec<&str> = used_keys.iter().map(|k| k.key.as_str()).collect();
+        assert_eq!(keys, vec!["greetings.hello"]);
+    }
+
     #[test]
     fn test_scan_file_content_namespaced_t_macro() {
         let content = r#"
@@ -219,7 +1026,7 @@ mod tests {
         "#;
         let mut used_keys = Vec::new();
         
-        scan_file_content(content, "test.rs", &mut used_keys).unwrap();
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
         
         // 打印所有找到的键，帮助调试
         println!("找到的键:");
@@ -236,6 +1043,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_scan_file_content_dynamic_key() {
         let content = r#"
         fn main() {
@@ -245,7 +1053,7 @@ mod tests {
         "#;
         let mut used_keys = Vec::new();
         
-        scan_file_content(content, "test.rs", &mut used_keys).unwrap();
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
         
         assert_eq!(used_keys.len(), 1);
         assert_eq!(used_keys[0].key, "dynamic.key");
@@ -262,7 +1070,7 @@ mod tests {
         "#;
         let mut used_keys = Vec::new();
         
-        scan_file_content(content, "test.rs", &mut used_keys).unwrap();
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
         
         assert_eq!(used_keys.len(), 2);
         assert_eq!(used_keys[0].key, "user.welcome");
@@ -303,7 +1111,7 @@ mod tests {
         "#;
         
         let mut used_keys = Vec::new();
-        scan_file_content(content, "mini_test.rs", &mut used_keys).unwrap();
+        scan_file_content(content, "mini_test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
         
         // 验证扫描结果
         assert!(used_keys.len() >= 4, "应当至少检测到4个翻译键，实际检测到: {}", used_keys.len());
@@ -324,4 +1132,244 @@ mod tests {
         assert_eq!(dynamic_keys.len(), 1, "应当检测到1个动态键");
         assert_eq!(dynamic_keys[0].key, "dynamic.key");
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_scan_file_content_fluent() {
+        let content = r#"
+        fn main() {
+            println!("{}", fl!(LANGUAGE_LOADER, "hello-world"));
+            println!("{}", fl!(loader(), "greeting", name = "张三"));
+            println!("{}", i18n_embed_fl::fl!("goodbye"));
+            let title = LANGUAGE_LOADER.get("app-title");
+            let placeholder = language_loader.get_attr("login-input", "placeholder");
+            let value = map.get("not-a-key");
+        }
+        "#;
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
+        
+        let keys: Vec<&str> = used_keys.iter().map(|k| k.key.as_str()).collect();
+        assert_eq!(keys, vec!["hello-world", "greeting", "goodbye", "app-title", "login-input.placeholder"]);
+    }
+
+    #[test]
+    fn test_scan_file_content_constants() {
+        let sources = [
+            ("src/keys.rs", r#"
+            pub const LOGIN_TITLE: &str = "auth.login.title";
+            pub static ERRORS: [&str; 2] = [
+                "errors.not_found",
+                "errors.forbidden",
+            ];
+            "#),
+            ("src/main.rs", r#"
+            const LOGIN_TITLE: &'static str = "main.login.title";
+
+            fn main() {
+                println!("{}", t!(LOGIN_TITLE));
+                println!("{}", t!(keys::LOGIN_TITLE));
+                println!("{}", t!(crate::keys::ERRORS[index], code = 404));
+            }
+            "#),
+        ];
+        let symbols = SymbolTable::from_sources(sources);
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(sources[1].1, sources[1].0, &symbols, &mut used_keys).unwrap();
+        
+        let keys: Vec<(&str, KeyResolution)> = used_keys.iter()
+            .map(|k| (k.key.as_str(), k.resolution))
+            .collect();
+        assert_eq!(keys, vec![
+            ("main.login.title", KeyResolution::Constant),
+            ("auth.login.title", KeyResolution::Constant),
+            ("errors.not_found", KeyResolution::ArrayElement),
+            ("errors.forbidden", KeyResolution::ArrayElement),
+        ]);
+        assert!(used_keys.iter().all(|k| k.is_literal));
+    }
+
+    #[test]
+    fn test_scan_file_content_method_keys() {
+        let content = r#"
+        enum Error { NotFound, Forbidden, Other }
+
+        impl Error {
+            fn key(&self) -> &'static str {
+                match self {
+                    Error::NotFound => "errors.not_found",
+                    Error::Forbidden => { "errors.forbidden" }
+                    Error::Other => return "errors.other",
+                }
+            }
+
+            fn describe(&self) -> String {
+                format!("{}", "not.a.key")
+            }
+        }
+
+        fn main() {
+            println!("{}", t!(err.key(), code = 404));
+        }
+        "#;
+        let symbols = SymbolTable::from_sources([("src/main.rs", content)]);
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(content, "src/main.rs", &symbols, &mut used_keys).unwrap();
+        
+        let keys: Vec<&str> = used_keys.iter().map(|k| k.key.as_str()).collect();
+        assert_eq!(keys, vec!["errors.not_found", "errors.forbidden", "errors.other"]);
+        assert!(used_keys.iter().all(|k| k.resolution == KeyResolution::Function));
+    }
+
+    #[test]
+    fn test_scan_file_content_macro_patterns() {
+        let content = r#"
+        fn main() {
+            println!("{}", t!(&format!("status.{}", state)));
+            println!("{}", t!(format!("item_{id}.{{label}}"), count = 1));
+            println!("{}", t!(concat!("menu.", "file")));
+            println!("{}", t!(concat!("menu.", env!("MENU"))));
+            println!("{}", t!(format!("menu.edit")));
+            let message = format!("not.{}", "a.key");
+        }
+        "#;
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
+        
+        // 全部由字面量组成的键是具体的键
+        let keys: Vec<(&str, bool, KeyResolution)> = used_keys.iter()
+            .map(|k| (k.key.as_str(), k.is_literal, k.resolution))
+            .collect();
+        assert_eq!(keys, vec![
+            ("status.*", false, KeyResolution::Pattern),
+            ("item_*.{label}", false, KeyResolution::Pattern),
+            ("menu.file", true, KeyResolution::Literal),
+            ("menu.*", false, KeyResolution::Pattern),
+            ("menu.edit", true, KeyResolution::Literal),
+        ]);
+    }
+
+    #[test]
+    fn test_scan_file_content_enum_variants() {
+        let content = r#"
+        #[derive(Debug, strum::AsRefStr, strum::Display)]
+        #[strum(serialize_all = "snake_case")]
+        pub enum Role {
+            Admin,
+            /// 访客
+            GuestUser,
+            #[strum(serialize = "owner")]
+            SuperAdmin,
+        }
+
+        fn label(role: &Role, kind: &str) -> String {
+            let a = t!(&format!("role.{}", role.as_ref()));
+            let b = t!(&format!("role.{}.{}", role, kind));
+            a + &b
+        }
+        "#;
+        let symbols = SymbolTable::from_sources([("src/main.rs", content)]);
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(content, "src/main.rs", &symbols, &mut used_keys).unwrap();
+        
+        let keys: Vec<(&str, bool)> = used_keys.iter().map(|k| (k.key.as_str(), k.is_literal)).collect();
+        assert_eq!(keys, vec![
+            ("role.admin", true),
+            ("role.guest_user", true),
+            ("role.owner", true),
+            ("role.admin.*", false),
+            ("role.guest_user.*", false),
+            ("role.owner.*", false),
+        ]);
+    }
+
+    #[test]
+    fn test_scan_file_content_ui_macros() {
+        let content = r#"
+        fn app() -> Element {
+            rsx! {
+                p { "{t!(\"greetings.hello\")}" }
+                p {
+                    {t!(
+                        "greetings.goodbye",
+                        name = name
+                    )}
+                }
+            }
+        }
+
+        fn leptos_view(i18n: I18n) -> impl IntoView {
+            view! { <h1>{t!(i18n, home.title)}</h1> }
+        }
+        "#;
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
+        
+        let keys: Vec<(&str, usize)> = used_keys.iter().map(|k| (k.key.as_str(), k.line_number)).collect();
+        assert_eq!(keys, vec![("greetings.hello", 4), ("greetings.goodbye", 6), ("home.title", 15)]);
+    }
+
+    #[test]
+    fn test_scan_file_content_ignores_comments_and_literals() {
+        let content = r#"
+        /// 用法：`t!("doc.key")`
+        fn main() {
+            // t!("line.comment");
+            /* t!("block.comment") */
+            // 未闭合的 t!(
+            println!("{}", t!("greetings.hello"));
+            let example = "t!(\"string.literal\")";
+            println!("{}", t!("greetings.goodbye"));
+        }
+        "#;
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
+        
+        let keys: Vec<(&str, usize)> = used_keys.iter().map(|k| (k.key.as_str(), k.line_number)).collect();
+        assert_eq!(keys, vec![("greetings.hello", 7), ("greetings.goodbye", 9)]);
+    }
+
+    #[test]
+    fn test_scan_template_content() {
+        let content = r#"
+        <h1>{{ t("greetings.hello") }}</h1>
+        <p>{{ t(key='user.welcome', name=user.name) }}</p>
+        <p>{{ "greetings.goodbye" | t }}</p>
+        "#;
+        let mut used_keys = Vec::new();
+        
+        scan_template_content(content, "templates/index.html", &mut used_keys).unwrap();
+        
+        let keys: Vec<&str> = used_keys.iter().map(|k| k.key.as_str()).collect();
+        assert_eq!(keys, vec!["greetings.hello", "user.welcome", "greetings.goodbye"]);
+    }
+
+    #[test]
+    fn test_scan_file_content_explicit_locale() {
+        let content = r#"
+        fn main() {
+            println!("{}", t!("greetings.hello", locale = "fr"));
+            println!("{}", rust_i18n::t!("user.welcome", name = "Anna", locale = "de"));
+            println!("{}", t!("greetings.goodbye", locale = current));
+        }
+        "#;
+        let mut used_keys = Vec::new();
+        
+        scan_file_content(content, "test.rs", &SymbolTable::default(), &mut used_keys).unwrap();
+        
+        let locales: Vec<(&str, Option<&str>)> = used_keys.iter()
+            .map(|k| (k.key.as_str(), k.locale.as_deref()))
+            .collect();
+        assert_eq!(locales, vec![
+            ("greetings.hello", Some("fr")),
+            ("user.welcome", Some("de")),
+            ("greetings.goodbye", None),
+        ]);
+    }
+}
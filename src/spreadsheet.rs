@@ -0,0 +1,279 @@
+use anyhow::{Result, Context, bail};
+use log::{info, warn};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::analyzer::AnalysisResult;
+use crate::config::Config;
+use crate::locale_writer::{self, KeyUpdate};
+use crate::parser::{self, DefinedKey};
+use crate::scanner::UsedKey;
+
+/// 翻译键列名
+const KEY_COLUMN: &str = "key";
+/// 来源文件列名
+const SOURCE_FILE_COLUMN: &str = "source_file";
+/// 使用次数列名
+const USAGE_COUNT_COLUMN: &str = "usage_count";
+/// 未使用标记列名
+const UNUSED_COLUMN: &str = "unused";
+
+/// 导出所有翻译键为 CSV 矩阵：每行一个键，每种语言一列，附带来源文件、使用次数和未使用标记
+pub fn export(
+    defined_keys: &[DefinedKey],
+    usages: &[UsedKey],
+    analysis_result: &AnalysisResult,
+    config: &Config,
+) -> Result<String> {
+    // 默认语言排在第一列，其余语言按字母顺序排列
+    let mut languages: Vec<&str> = defined_keys.iter()
+        .map(|k| k.language.as_str())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    languages.sort_by_key(|lang| *lang != config.default_locale);
+
+    let mut rows: BTreeMap<&str, HashMap<&str, &DefinedKey>> = BTreeMap::new();
+    for def_key in defined_keys {
+        rows.entry(def_key.key.as_str())
+            .or_default()
+            .insert(def_key.language.as_str(), def_key);
+    }
+
+    let mut usage_counts: HashMap<&str, usize> = HashMap::new();
+    for used_key in usages {
+        *usage_counts.entry(used_key.key.as_str()).or_default() += 1;
+    }
+
+    let unused: HashSet<&str> = analysis_result.unused_keys.values()
+        .flatten()
+        .map(|k| k.key.as_str())
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    let mut header = vec![KEY_COLUMN];
+    header.extend(&languages);
+    header.extend([SOURCE_FILE_COLUMN, USAGE_COUNT_COLUMN, UNUSED_COLUMN]);
+    writer.write_record(&header)?;
+
+    for (key, translations) in &rows {
+        // 来源文件优先使用默认语言中的定义
+        let source_file = translations.get(config.default_locale.as_str())
+            .or_else(|| translations.values().next())
+            .map(|k| k.file_path.as_str())
+            .unwrap_or_default();
+
+        let mut record = vec![key.to_string()];
+        for language in &languages {
+            record.push(translations.get(language).map(|k| k.value.clone()).unwrap_or_default());
+        }
+        record.push(source_file.to_string());
+        record.push(usage_counts.get(key).copied().unwrap_or(0).to_string());
+        record.push(unused.contains(key).to_string());
+
+        writer.write_record(&record)?;
+    }
+
+    info!("导出 {} 个翻译键，{} 种语言", rows.len(), languages.len());
+
+    let data = writer.into_inner().context("无法生成 CSV")?;
+    Ok(String::from_utf8(data)?)
+}
+
+/// 被拒绝的 CSV 行
+#[derive(Debug)]
+pub struct RejectedRow {
+    /// 行号（从 1 开始，包含表头）
+    pub line: usize,
+    /// 翻译键
+    pub key: String,
+    /// 拒绝原因
+    pub reason: String,
+}
+
+/// CSV 导入结果
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    /// 写入的翻译数量
+    pub updated: usize,
+    /// 被拒绝的行
+    pub rejected: Vec<RejectedRow>,
+}
+
+/// 将编辑后的 CSV 写回对应的翻译文件
+///
+/// 引入未知翻译键、未知语言或占位符与默认语言不一致的行会被整行拒绝。
+/// 所有行校验完成且全部修改都能写入后才修改文件。
+pub fn import(content: &str, defined_keys: &[DefinedKey], config: &Config) -> Result<ImportSummary> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let header = reader.headers()?.clone();
+
+    if header.get(0) != Some(KEY_COLUMN) {
+        bail!("CSV 第一列必须是 {}", KEY_COLUMN);
+    }
+
+    // 除固定列外的列都是语言列
+    let language_columns: Vec<(usize, &str)> = header.iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, name)| ![SOURCE_FILE_COLUMN, USAGE_COUNT_COLUMN, UNUSED_COLUMN].contains(name))
+        .collect();
+
+    let current: HashMap<(&str, &str), &str> = defined_keys.iter()
+        .map(|k| ((k.key.as_str(), k.language.as_str()), k.value.as_str()))
+        .collect();
+    let languages: HashSet<&str> = defined_keys.iter().map(|k| k.language.as_str()).collect();
+
+    let mut summary = ImportSummary::default();
+    let mut records = Vec::new();
+
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        let line = index + 2;
+        let key = record.get(0).unwrap_or_default().trim().to_string();
+        records.push((line, key, record));
+    }
+
+    let mut updates = Vec::new();
+    for (line, key, record) in &records {
+        let reject = |reason: String| RejectedRow { line: *line, key: key.clone(), reason };
+
+        let Some(source_value) = current.get(&(key.as_str(), config.default_locale.as_str())) else {
+            summary.rejected.push(reject(format!("默认语言 {} 中不存在该翻译键", config.default_locale)));
+            continue;
+        };
+        let expected_placeholders = parser::extract_placeholders(source_value);
+
+        // 先校验整行，再写入，避免只应用一部分修改
+        let mut changes = Vec::new();
+        let mut error = None;
+        for (column, language) in &language_columns {
+            let value = record.get(*column).unwrap_or_default();
+            if value.is_empty() || current.get(&(key.as_str(), language)) == Some(&value) {
+                continue;
+            }
+
+            if !languages.contains(language) {
+                error = Some(format!("项目中没有语言 {} 的翻译文件", language));
+                break;
+            }
+
+            let placeholders = parser::extract_placeholders(value);
+            if placeholders != expected_placeholders {
+                error = Some(format!(
+                    "{} 的占位符 {:?} 与默认语言 {:?} 不一致",
+                    language, placeholders, expected_placeholders
+                ));
+                break;
+            }
+            changes.push((*language, value));
+        }
+
+        if let Some(reason) = error {
+            warn!("拒绝第 {} 行 ({}): {}", line, key, reason);
+            summary.rejected.push(reject(reason));
+            continue;
+        }
+
+        for (language, value) in changes {
+            updates.push(KeyUpdate {
+                path: locale_writer::locate_target_file(key, language, defined_keys, config),
+                language,
+                key,
+                value,
+            });
+        }
+    }
+
+    locale_writer::set_keys(&updates)?;
+    summary.updated = updates.len();
+
+    info!("导入完成: 更新 {} 个翻译，拒绝 {} 行", summary.updated, summary.rejected.len());
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer;
+    use crate::audit::AuditBuilder;
+    use crate::scanner::{KeyResolution, UsageContext};
+    use std::fs;
+
+    fn defined(key: &str, language: &str, value: &str) -> DefinedKey {
+        DefinedKey {
+            key: key.to_string(),
+            language: language.to_string(),
+            value: value.to_string(),
+            file_path: format!("locales/{}.yml", language),
+        }
+    }
+
+    fn used(key: &str) -> UsedKey {
+        UsedKey {
+            key: key.to_string(),
+            is_literal: true,
+            file_path: "src/main.rs".to_string(),
+            line_number: 1,
+            column: 1,
+            resolution: KeyResolution::Literal,
+            disabled_by: None,
+            context: UsageContext::Production,
+            locale: None,
+        }
+    }
+
+    #[test]
+    fn test_export_matrix() {
+        let project = tempfile::tempdir().unwrap();
+        let config = AuditBuilder::new(project.path()).build().unwrap().into_config();
+        let defined_keys = vec![
+            defined("greeting", "fr", "Bonjour, \"%{name}\""),
+            defined("greeting", "en", "Hello, \"%{name}\""),
+            defined("farewell", "en", "Goodbye\nSee you"),
+        ];
+        let usages = vec![used("greeting"), used("greeting")];
+        let result = analyzer::analyze(&usages, &defined_keys, &config).unwrap();
+
+        let csv = export(&defined_keys, &usages, &result, &config).unwrap();
+        assert_eq!(
+            csv,
+            "key,en,fr,source_file,usage_count,unused\n\
+             farewell,\"Goodbye\nSee you\",,locales/en.yml,0,true\n\
+             greeting,\"Hello, \"\"%{name}\"\"\",\"Bonjour, \"\"%{name}\"\"\",locales/en.yml,2,false\n"
+        );
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_rows() {
+        let project = tempfile::tempdir().unwrap();
+        fs::create_dir_all(project.path().join("locales")).unwrap();
+        fs::write(project.path().join("locales/fr.yml"), "greeting: Bonjour %{name}\nfarewell: Au revoir\n").unwrap();
+        let config = AuditBuilder::new(project.path()).build().unwrap().into_config();
+        let defined_keys = vec![
+            defined("greeting", "en", "Hello %{name}"),
+            defined("farewell", "en", "Goodbye"),
+            defined("greeting", "fr", "Bonjour %{name}"),
+            defined("farewell", "fr", "Au revoir"),
+        ];
+
+        let content = "key,en,fr,de,source_file\n\
+                       greeting,Hello %{name},Salut { $name },,locales/en.yml\n\
+                       farewell,Goodbye,À bientôt,,locales/en.yml\n\
+                       unknown.key,Unknown,Inconnu,,\n\
+                       farewell,Goodbye,Adieu,Tschüss,locales/en.yml\n";
+        let summary = import(content, &defined_keys, &config).unwrap();
+
+        let rejected: Vec<(usize, &str)> = summary.rejected.iter().map(|r| (r.line, r.key.as_str())).collect();
+        assert_eq!(rejected, vec![(2, "greeting"), (4, "unknown.key"), (5, "farewell")]);
+        assert!(summary.rejected[0].reason.contains("{ $name }"));
+        assert!(summary.rejected[2].reason.contains("de"));
+
+        assert_eq!(summary.updated, 1);
+        assert_eq!(
+            fs::read_to_string(project.path().join("locales/fr.yml")).unwrap(),
+            "greeting: Bonjour %{name}\nfarewell: À bientôt\n"
+        );
+    }
+}
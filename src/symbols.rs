@@ -0,0 +1,478 @@
+use log::debug;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::syntax;
+
+/// 字符串常量的定义
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// 定义所在的模块路径（包括 `mod` 块和 `impl` 块的类型名）
+    pub module_path: Vec<String>,
+    /// 常量的值；数组常量包含所有元素
+    pub values: Vec<String>,
+    /// 是否是数组常量
+    pub is_array: bool,
+    /// 所在文件路径
+    pub file_path: String,
+}
+
+/// crate 范围内字符串常量的符号表
+///
+/// 收集 `const NAME: &str = "..."`、`static NAME: &str = "..."` 以及
+/// `[&str; N]` / `&[&str]` 类型的数组常量，按常量名索引；
+/// 同时收集返回 `&str` 的方法及其可能返回的字符串字面量，按方法名索引；
+/// 以及派生了 strum `AsRefStr` / `Display` 等的枚举及其各变体的字符串形式，按类型名索引。
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    constants: HashMap<String, Vec<Symbol>>,
+    methods: HashMap<String, Vec<Symbol>>,
+    enums: HashMap<String, Vec<Symbol>>,
+}
+
+impl SymbolTable {
+    /// 从一组源文件 (路径, 内容) 构建符号表
+    pub fn from_sources<'a>(sources: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut table = SymbolTable::default();
+        for (file_path, content) in sources {
+            table.add_source(file_path, content);
+        }
+        table
+    }
+
+    /// 收集单个源文件中的字符串常量
+    pub fn add_source(&mut self, file_path: &str, content: &str) {
+        let const_regex = Regex::new(
+            r"(?m)^[ \t]*(?:pub(?:\([^)]*\))?\s+)?(?:const|static)\s+(?:mut\s+)?([A-Za-z_][A-Za-z0-9_]*)\s*:\s*((?:\[[^\]]*\]|[^=;\[])+)="
+        ).unwrap();
+
+        let base_path = module_path_of_file(file_path);
+        let scopes = named_scopes(content);
+
+        for caps in const_regex.captures_iter(content) {
+            let name = caps[1].to_string();
+            let ty = caps[2].trim();
+            if !ty.contains("str") {
+                continue;
+            }
+
+            let value_start = caps.get(0).unwrap().end();
+            let Some(value_end) = syntax::find_at_depth_zero(content, value_start, b';') else {
+                continue;
+            };
+            let value = content[value_start..value_end].trim();
+
+            let is_array = ty.contains('[');
+            let is_literal = value.starts_with('"') || value.starts_with("r\"") || value.starts_with("r#");
+            if !is_array && !is_literal {
+                continue;
+            }
+
+            let values = syntax::string_literals(value);
+            if values.is_empty() {
+                continue;
+            }
+
+            let mut module_path = base_path.clone();
+            module_path.extend(
+                scopes.iter()
+                    .filter(|scope| scope.start < value_start && value_start < scope.end)
+                    .map(|scope| scope.name.clone())
+            );
+
+            debug!("在 {} 中找到字符串常量 {}::{} = {:?}", file_path, module_path.join("::"), name, values);
+
+            self.constants.entry(name).or_default().push(Symbol {
+                module_path,
+                values,
+                is_array,
+                file_path: file_path.to_string(),
+            });
+        }
+
+        self.add_methods(file_path, content, &base_path, &scopes);
+        self.add_enums(file_path, content, &base_path, &scopes);
+    }
+
+    /// 收集派生了 strum 字符串转换的枚举，按 strum 的规则计算每个变体的字符串形式
+    fn add_enums(&mut self, file_path: &str, content: &str, base_path: &[String], scopes: &[Scope]) {
+        let enum_regex = Regex::new(r"\benum\s+([A-Za-z_][A-Za-z0-9_]*)\s*(?:<[^{;]*>)?\s*\{").unwrap();
+
+        for caps in enum_regex.captures_iter(content) {
+            let whole = caps.get(0).unwrap();
+            let name = caps[1].to_string();
+
+            // 枚举之前的属性：从上一个条目结束处（`;` 或 `}`）到 enum 关键字
+            let attrs_start = content[..whole.start()].rfind([';', '}']).map(|i| i + 1).unwrap_or(0);
+            let attrs = &content[attrs_start..whole.start()];
+            if !derives_string_conversion(attrs) {
+                continue;
+            }
+            let casing = strum_argument(attrs, "serialize_all");
+
+            let body_open = whole.end() - 1;
+            let Some(body_close) = syntax::find_matching_delimiter(content, body_open) else {
+                continue;
+            };
+
+            let values: Vec<String> = syntax::split_top_level(&content[body_open + 1..body_close], b',')
+                .into_iter()
+                .filter_map(|variant| variant_string(variant, casing.as_deref()))
+                .collect();
+            if values.is_empty() {
+                continue;
+            }
+
+            let mut module_path = base_path.to_vec();
+            module_path.extend(
+                scopes.iter()
+                    .filter(|scope| scope.start < body_open && body_open < scope.end)
+                    .map(|scope| scope.name.clone())
+            );
+
+            debug!("在 {} 中找到枚举 {}::{} -> {:?}", file_path, module_path.join("::"), name, values);
+
+            self.enums.entry(name).or_default().push(Symbol {
+                module_path,
+                values,
+                is_array: false,
+                file_path: file_path.to_string(),
+            });
+        }
+    }
+
+    /// 收集返回字符串的方法（带有 `self` 参数且返回类型包含 `str`）
+    fn add_methods(&mut self, file_path: &str, content: &str, base_path: &[String], scopes: &[Scope]) {
+        let fn_regex = Regex::new(r"\bfn\s+([A-Za-z_][A-Za-z0-9_]*)\s*(?:<[^>]*>)?\s*\(").unwrap();
+
+        for caps in fn_regex.captures_iter(content) {
+            let name = caps[1].to_string();
+            let params_open = caps.get(0).unwrap().end() - 1;
+            let Some(params_close) = syntax::find_matching_delimiter(content, params_open) else {
+                continue;
+            };
+            if !content[params_open..params_close].contains("self") {
+                continue;
+            }
+
+            // 返回类型位于参数列表与函数体之间
+            let Some(body_open) = content[params_close..].find(['{', ';']).map(|i| params_close + i) else {
+                continue;
+            };
+            let signature_tail = &content[params_close + 1..body_open];
+            let return_type = signature_tail.split("where").next().unwrap_or_default();
+            if content.as_bytes()[body_open] != b'{' || !return_type.contains("->") || !return_type.contains("str") {
+                continue;
+            }
+
+            let Some(body_close) = syntax::find_matching_delimiter(content, body_open) else {
+                continue;
+            };
+            let values = returned_literals(&content[body_open..=body_close]);
+            if values.is_empty() {
+                continue;
+            }
+
+            let mut module_path = base_path.to_vec();
+            module_path.extend(
+                scopes.iter()
+                    .filter(|scope| scope.start < body_open && body_open < scope.end)
+                    .map(|scope| scope.name.clone())
+            );
+
+            debug!("在 {} 中找到返回字符串的方法 {}::{} -> {:?}", file_path, module_path.join("::"), name, values);
+
+            self.methods.entry(name).or_default().push(Symbol {
+                module_path,
+                values,
+                is_array: false,
+                file_path: file_path.to_string(),
+            });
+        }
+    }
+
+    /// 解析方法调用（例如 `err.key()` 中的 `key`），返回所有同名的返回字符串的方法
+    ///
+    /// 无法确定接收者的类型，因此优先选择与调用位置处于同一文件的定义，否则返回所有候选。
+    pub fn resolve_method(&self, name: &str, from_file: &str) -> Vec<&Symbol> {
+        let Some(candidates) = self.methods.get(name) else {
+            return Vec::new();
+        };
+
+        let same_file: Vec<&Symbol> = candidates.iter()
+            .filter(|symbol| symbol.file_path == from_file)
+            .collect();
+        if !same_file.is_empty() {
+            return same_file;
+        }
+
+        candidates.iter().collect()
+    }
+
+    /// 解析常量路径（例如 `CONST`、`module::CONST`、`Self::CONST`），返回匹配的常量定义
+    ///
+    /// 存在多个同名常量时，优先选择路径限定符匹配的定义，其次是与使用位置处于同一文件的定义；
+    /// 仍无法区分时返回所有候选，以免把实际使用的键误报为未使用。
+    pub fn resolve(&self, path: &str, from_file: &str) -> Vec<&Symbol> {
+        resolve_path(&self.constants, path, from_file)
+    }
+
+    /// 解析枚举类型路径（例如 `Role`、`models::Role`），返回匹配的枚举定义，规则与 [`SymbolTable::resolve`] 相同
+    pub fn resolve_enum(&self, path: &str, from_file: &str) -> Vec<&Symbol> {
+        resolve_path(&self.enums, path, from_file)
+    }
+}
+
+/// 按路径在某一类符号中查找定义
+fn resolve_path<'a>(symbols: &'a HashMap<String, Vec<Symbol>>, path: &str, from_file: &str) -> Vec<&'a Symbol> {
+    let mut segments: Vec<&str> = path.split("::").map(str::trim).collect();
+    let Some(name) = segments.pop() else {
+        return Vec::new();
+    };
+    let Some(candidates) = symbols.get(name) else {
+        return Vec::new();
+    };
+
+    let qualifier: Vec<&str> = segments.into_iter()
+        .filter(|s| !matches!(*s, "crate" | "self" | "super" | "Self" | ""))
+        .collect();
+
+    if !qualifier.is_empty() {
+        let qualified: Vec<&Symbol> = candidates.iter()
+            .filter(|symbol| symbol.module_path.len() >= qualifier.len()
+                && symbol.module_path[symbol.module_path.len() - qualifier.len()..] == qualifier[..])
+            .collect();
+        if !qualified.is_empty() {
+            return qualified;
+        }
+    }
+
+    if candidates.len() > 1 {
+        let same_file: Vec<&Symbol> = candidates.iter()
+            .filter(|symbol| symbol.file_path == from_file)
+            .collect();
+        if !same_file.is_empty() {
+            return same_file;
+        }
+    }
+
+    candidates.iter().collect()
+}
+
+/// 提取函数体中可能作为返回值的字符串字面量
+///
+/// 只收集处于返回位置的字面量：`match` 分支的结果（`=> "key"`）、`return "key"`、
+/// 以及代码块的尾表达式（`{ "key" }`），忽略 `"...".to_string()` 或宏参数中的字面量。
+fn returned_literals(body: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    for (span, value) in syntax::string_literal_spans(body) {
+        let before = body[..span.start].trim_end();
+        let after = body[span.end..].trim_start();
+
+        let in_return_position = before.ends_with("=>")
+            || before.ends_with('{')
+            || before.strip_suffix("return").is_some_and(|rest| !rest.ends_with(|c: char| c.is_alphanumeric() || c == '_'));
+        let ends_expression = after.starts_with([',', '}', ';']) || after.is_empty();
+
+        if in_return_position && ends_expression && !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// 判断属性中是否派生了 strum 的字符串转换（`AsRefStr`、`Display`、`IntoStaticStr`、`EnumString`）
+fn derives_string_conversion(attrs: &str) -> bool {
+    let derive_regex = Regex::new(r"derive\s*\(([^)]*)\)").unwrap();
+    derive_regex.captures_iter(attrs).any(|caps| {
+        caps[1].split(',')
+            .map(|d| d.trim().rsplit("::").next().unwrap_or_default())
+            .any(|d| matches!(d, "AsRefStr" | "Display" | "IntoStaticStr" | "EnumString"))
+    })
+}
+
+/// 读取 `#[strum(...)]` 属性中某个参数的字符串值，例如 `serialize_all = "snake_case"`
+fn strum_argument(attrs: &str, name: &str) -> Option<String> {
+    let regex = Regex::new(&format!(r#"strum\s*\([^)]*\b{}\s*=\s*"([^"]*)""#, name)).ok()?;
+    regex.captures(attrs).map(|caps| caps[1].to_string())
+}
+
+/// 计算枚举变体的字符串形式：优先使用 `to_string`，其次是 `serialize`，最后按 `serialize_all` 转换变体名
+fn variant_string(variant: &str, casing: Option<&str>) -> Option<String> {
+    // 变体名位于所有属性和文档注释之后
+    let mut rest = variant.trim();
+    let mut attrs = String::new();
+    loop {
+        if rest.starts_with("#[") {
+            let end = syntax::find_matching_delimiter(rest, 1)?;
+            attrs.push_str(&rest[..=end]);
+            rest = rest[end + 1..].trim_start();
+        } else if rest.starts_with("//") {
+            rest = rest.split_once('\n').map(|(_, r)| r.trim_start()).unwrap_or_default();
+        } else {
+            break;
+        }
+    }
+
+    let strum_disabled = Regex::new(r"strum\s*\([^)]*\bdisabled\b").unwrap();
+    if strum_disabled.is_match(&attrs) {
+        return None;
+    }
+
+    let name: String = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+    if name.is_empty() {
+        return None;
+    }
+
+    if let Some(value) = strum_argument(&attrs, "to_string").or_else(|| strum_argument(&attrs, "serialize")) {
+        return Some(value);
+    }
+
+    Some(match casing {
+        Some(casing) => convert_case(&name, casing),
+        None => name,
+    })
+}
+
+/// 按 strum `serialize_all` 的命名规则转换变体名
+fn convert_case(name: &str, casing: &str) -> String {
+    let words = split_words(name);
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+        chars.next()
+            .map(|first| first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect())
+            .unwrap_or_default()
+    };
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    let upper: Vec<String> = words.iter().map(|w| w.to_uppercase()).collect();
+    let title: Vec<String> = words.iter().map(|w| capitalize(w)).collect();
+
+    match casing {
+        "snake_case" => lower.join("_"),
+        "kebab-case" => lower.join("-"),
+        "SCREAMING_SNAKE_CASE" | "shouty_snake_case" => upper.join("_"),
+        "SCREAMING-KEBAB-CASE" => upper.join("-"),
+        "lowercase" => lower.concat(),
+        "UPPERCASE" => upper.concat(),
+        "title_case" | "Title Case" => title.join(" "),
+        "Train-Case" => title.join("-"),
+        "PascalCase" | "UpperCamelCase" => title.concat(),
+        "camelCase" | "lowerCamelCase" => {
+            let mut result = lower.first().cloned().unwrap_or_default();
+            result.push_str(&title[1.min(title.len())..].concat());
+            result
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// 把 PascalCase 名称拆分为单词，例如 `HTTPServerError` 拆分为 `HTTP`、`Server`、`Error`
+fn split_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_uppercase() && prev.is_some_and(|p| {
+            p.is_lowercase() || p.is_ascii_digit() || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+        });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// 源文件中的具名作用域（`mod name { ... }` 或 `impl Type { ... }`）
+struct Scope {
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+/// 查找源文件中所有的 `mod` 块和 `impl` 块
+fn named_scopes(content: &str) -> Vec<Scope> {
+    let scope_regex = Regex::new(
+        r"\b(?:mod\s+([A-Za-z_][A-Za-z0-9_]*)|impl(?:\s*<[^{;]*?>)?\s+(?:[^{;]*?\s+for\s+)?(?:[A-Za-z_][A-Za-z0-9_]*::)*([A-Za-z_][A-Za-z0-9_]*)(?:\s*<[^{;]*>)?(?:\s+where[^{]*)?)\s*\{"
+    ).unwrap();
+
+    scope_regex.captures_iter(content)
+        .filter_map(|caps| {
+            let whole = caps.get(0)?;
+            let name = caps.get(1).or_else(|| caps.get(2))?.as_str().to_string();
+            let open = whole.end() - 1;
+            let end = syntax::find_matching_delimiter(content, open)?;
+            Some(Scope { name, start: open, end })
+        })
+        .collect()
+}
+
+/// 根据源文件路径推断模块路径，例如 `src/auth/login.rs` 对应 `auth::login`
+pub fn module_path_of_file(file_path: &str) -> Vec<String> {
+    let path = Path::new(file_path);
+    let components: Vec<String> = path.components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .map(|s| s.to_string())
+        .collect();
+
+    // 从 src 目录之后开始计算模块路径
+    let start = components.iter()
+        .rposition(|c| c == "src")
+        .map(|i| i + 1)
+        .unwrap_or(0);
+
+    let mut module_path: Vec<String> = components[start..].to_vec();
+    if let Some(last) = module_path.pop() {
+        let stem = last.trim_end_matches(".rs");
+        if !matches!(stem, "main" | "lib" | "mod") {
+            module_path.push(stem.to_string());
+        }
+    }
+    module_path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_table_constants() {
+        let table = SymbolTable::from_sources([
+            ("src/main.rs", r#"
+const TITLE: &str = "app.title";
+pub static SUBTITLE: &'static str = "app.subtitle";
+const COUNT: usize = 3;
+const MENU: [&str; 2] = ["menu.file", "menu.edit"];
+
+mod admin {
+    pub const TITLE: &str = "admin.title";
+}
+"#),
+            ("src/errors.rs", r#"pub(crate) const ERRORS: &[&str] = &["error.network", "error.timeout"];"#),
+        ]);
+
+        let values = |path: &str| -> Vec<(Vec<String>, bool)> {
+            table.resolve(path, "src/main.rs").into_iter().map(|s| (s.values.clone(), s.is_array)).collect()
+        };
+        // 同名常量无法区分时返回所有候选，带模块路径时只返回匹配的定义
+        assert_eq!(values("TITLE").len(), 2);
+        assert_eq!(values("admin::TITLE"), vec![(vec!["admin.title".to_string()], false)]);
+        assert_eq!(values("SUBTITLE"), vec![(vec!["app.subtitle".to_string()], false)]);
+        assert_eq!(values("MENU"), vec![(vec!["menu.file".to_string(), "menu.edit".to_string()], true)]);
+        assert_eq!(values("errors::ERRORS"), vec![(vec!["error.network".to_string(), "error.timeout".to_string()], true)]);
+        assert!(values("COUNT").is_empty());
+    }
+}
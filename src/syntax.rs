@@ -0,0 +1,337 @@
+//! 轻量级的 Rust 源码结构分析
+//!
+//! 扫描器基于正则表达式逐行工作，这里提供跨行分析所需的辅助函数：
+//! 匹配括号、提取字符串字面量等。这些函数会跳过字符串、字符字面量和注释，
+//! 但并不是完整的 Rust 解析器。
+
+use std::ops::Range;
+
+/// 从 `open` 位置的开括号（`(`、`[` 或 `{`）开始，查找与之匹配的闭括号位置
+pub fn find_matching_delimiter(content: &str, open: usize) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let (open_char, close_char) = match bytes.get(open)? {
+        b'(' => (b'(', b')'),
+        b'[' => (b'[', b']'),
+        b'{' => (b'{', b'}'),
+        _ => return None,
+    };
+
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = skip_string(bytes, i),
+            b'r' if is_raw_string_start(bytes, i) => i = skip_raw_string(bytes, i),
+            b'\'' => i = skip_char_literal(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            c if c == open_char => depth += 1,
+            c if c == close_char => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// 从 `start` 位置开始查找深度为 0 的某个字符（跳过括号、字符串和注释）
+pub fn find_at_depth_zero(content: &str, start: usize, target: u8) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            c if c == target => return Some(i),
+            b'(' | b'[' | b'{' => i = find_matching_delimiter(content, i)?,
+            b'"' => i = skip_string(bytes, i),
+            b'r' if is_raw_string_start(bytes, i) => i = skip_raw_string(bytes, i),
+            b'\'' => i = skip_char_literal(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// 按深度为 0 的分隔符拆分一段代码，例如拆分宏参数列表中的各个参数
+pub fn split_top_level(content: &str, separator: u8) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while let Some(end) = find_at_depth_zero(content, start, separator) {
+        parts.push(&content[start..end]);
+        start = end + 1;
+    }
+    parts.push(&content[start..]);
+    parts
+}
+
+/// 提取一段代码中的所有字符串字面量（包括原始字符串），并处理常见的转义
+pub fn string_literals(text: &str) -> Vec<String> {
+    string_literal_spans(text).into_iter().map(|(_, value)| value).collect()
+}
+
+/// 提取一段代码中的所有字符串字面量及其位置（从开头引号或 `r` 到结束引号之后）
+pub fn string_literal_spans(text: &str) -> Vec<(Range<usize>, String)> {
+    let bytes = text.as_bytes();
+    let mut literals = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let end = skip_string(bytes, i);
+                literals.push((i..(end + 1).min(text.len()), unescape(&text[i + 1..end.min(text.len())])));
+                i = end;
+            }
+            b'r' if is_raw_string_start(bytes, i) => {
+                let end = skip_raw_string(bytes, i);
+                let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+                let body_start = i + 2 + hashes;
+                let body_end = end - hashes;
+                if body_start <= body_end && body_end <= text.len() {
+                    literals.push((i..(end + 1).min(text.len()), text[body_start..body_end].to_string()));
+                }
+                i = end;
+            }
+            b'\'' => i = skip_char_literal(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    literals
+}
+
+/// 返回只保留代码的内容，用于查找宏调用
+///
+/// 注释中的字符替换为空格；字符串字面量中形如宏调用的 `name!(` 把 `!` 替换为空格，
+/// 但保留插值写法 `"{t!(\"key\")}"`（常见于 rsx! 等 UI 宏）。字符数和换行保持不变，
+/// 因此行号和列号与原内容一致。
+pub fn code_only(content: &str) -> String {
+    let bytes = content.as_bytes();
+    let mut masked: Vec<Range<usize>> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let end = skip_string(bytes, i);
+                masked.extend(macro_calls_in_literal(bytes, i + 1, end));
+                i = end;
+            }
+            b'r' if is_raw_string_start(bytes, i) => {
+                let end = skip_raw_string(bytes, i);
+                masked.extend(macro_calls_in_literal(bytes, i + 1, end));
+                i = end;
+            }
+            b'\'' => i = skip_char_literal(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let start = i;
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                masked.push(start..i);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                // 块注释可以嵌套
+                let start = i;
+                let mut depth = 0;
+                while i < bytes.len() {
+                    if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
+                        depth += 1;
+                        i += 1;
+                    } else if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
+                        depth -= 1;
+                        i += 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    i += 1;
+                }
+                masked.push(start..(i + 1).min(bytes.len()));
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let mut ranges = masked.into_iter().peekable();
+    content.char_indices()
+        .map(|(index, c)| {
+            while ranges.peek().is_some_and(|range| range.end <= index) {
+                ranges.next();
+            }
+            let is_masked = ranges.peek().is_some_and(|range| range.contains(&index));
+            if is_masked && c != '\n' { ' ' } else { c }
+        })
+        .collect()
+}
+
+/// 字符串字面量内容（`start..end`）中形如宏调用的 `!` 的位置，插值写法 `{name!(` 除外
+fn macro_calls_in_literal(bytes: &[u8], start: usize, end: usize) -> Vec<Range<usize>> {
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let mut calls = Vec::new();
+    for i in start..end.min(bytes.len()) {
+        if bytes[i] != b'!' || i == start || !is_ident(bytes[i - 1]) {
+            continue;
+        }
+        let after = bytes[i + 1..end].iter().position(|b| !b.is_ascii_whitespace());
+        if after.map(|offset| bytes[i + 1 + offset]) != Some(b'(') {
+            continue;
+        }
+        let mut name_start = i;
+        while name_start > start && (is_ident(bytes[name_start - 1]) || bytes[name_start - 1] == b':') {
+            name_start -= 1;
+        }
+        let before = bytes[start..name_start].iter().rposition(|b| !b.is_ascii_whitespace());
+        if before.map(|offset| bytes[start + offset]) != Some(b'{') {
+            calls.push(i..i + 1);
+        }
+    }
+    calls
+}
+
+/// 处理字符串字面量中的常见转义
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('0') => result.push('\0'),
+            // 行尾的反斜杠会连同下一行的前导空白一起忽略
+            Some('\n') => {
+                while chars.clone().next().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+/// 跳过以 `start` 处的 `"` 开始的普通字符串，返回结束引号的位置
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'"' => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// 判断 `start` 处是否是原始字符串 `r"..."` 或 `r#"..."#` 的开头
+fn is_raw_string_start(bytes: &[u8], start: usize) -> bool {
+    if start > 0 && (bytes[start - 1].is_ascii_alphanumeric() || bytes[start - 1] == b'_') {
+        return false;
+    }
+    let hashes = bytes[start + 1..].iter().take_while(|&&b| b == b'#').count();
+    bytes.get(start + 1 + hashes) == Some(&b'"')
+}
+
+/// 跳过原始字符串，返回结束引号之后最后一个 `#` 的位置
+fn skip_raw_string(bytes: &[u8], start: usize) -> usize {
+    let hashes = bytes[start + 1..].iter().take_while(|&&b| b == b'#').count();
+    let mut i = start + 2 + hashes;
+    while i < bytes.len() {
+        if bytes[i] == b'"' && bytes[i + 1..].iter().take(hashes).filter(|&&b| b == b'#').count() == hashes {
+            return i + hashes;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// 跳过字符字面量；对于生命周期标注（例如 `'static`）只跳过单引号本身
+fn skip_char_literal(bytes: &[u8], start: usize) -> usize {
+    match (bytes.get(start + 1), bytes.get(start + 2)) {
+        (Some(b'\\'), _) => {
+            let mut i = start + 2;
+            while i < bytes.len() && bytes[i] != b'\'' {
+                i += 1;
+            }
+            i
+        }
+        (Some(_), Some(b'\'')) => start + 2,
+        _ => {
+            // 多字节字符，例如 '中'：按 UTF-8 首字节确定字符长度，只检查之后的一个字节
+            let len = match bytes.get(start + 1) {
+                Some(0xC0..=0xDF) => 2,
+                Some(0xE0..=0xEF) => 3,
+                Some(0xF0..=0xF7) => 4,
+                _ => return start,
+            };
+            if bytes.get(start + 1 + len) == Some(&b'\'') {
+                start + 1 + len
+            } else {
+                start
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matching_delimiter() {
+        let content = r##"t!("a)", ')', /* ) */ r#"(")"# , b) // )"##;
+        assert_eq!(find_matching_delimiter(content, 2), Some(content.find("b)").unwrap() + 1));
+        assert_eq!(find_matching_delimiter("[a, {b}]", 0), Some(7));
+        assert_eq!(find_matching_delimiter("(a", 0), None);
+        assert_eq!(find_matching_delimiter("a", 0), None);
+    }
+
+    #[test]
+    fn test_split_top_level() {
+        assert_eq!(split_top_level(r#"a, f(b, c), "d, e""#, b','), vec!["a", " f(b, c)", r#" "d, e""#]);
+        assert_eq!(split_top_level("',', '(', '{', 'x'", b','), vec!["','", " '('", " '{'", " 'x'"]);
+        assert_eq!(split_top_level(r##"r#"a, "b""#, c"##, b','), vec![r##"r#"a, "b""#"##, " c"]);
+        assert_eq!(split_top_level("a /* b, c */, d // e, f", b','), vec!["a /* b, c */", " d // e, f"]);
+        assert_eq!(split_top_level("&'static str, T", b','), vec!["&'static str", " T"]);
+        assert_eq!(find_at_depth_zero("let x = ';' /* ; */; y", 0, b';'), Some(19));
+    }
+}
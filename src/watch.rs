@@ -0,0 +1,238 @@
+//! 监视模式：文件变化时重新审计，并输出审计结果的变化
+//!
+//! 监视源代码、模板和翻译文件目录。修改源文件时只重新扫描该文件，修改翻译文件时重新解析翻译文件。
+//! 短时间内的多次修改（例如编辑器保存时先写临时文件再重命名）合并为一次更新。
+
+use anyhow::{Result, Context};
+use colored::*;
+use log::{debug, warn};
+use notify::{RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::analyzer::AnalysisResult;
+use crate::config::Config;
+use crate::report;
+use crate::workspace::Workspace;
+
+/// 收到文件变化后等待后续变化的时间
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// 审计结果中的一项变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindingChange {
+    /// 新出现的缺少翻译
+    Missing { key: String, language: String },
+    /// 缺少的翻译已经补上
+    Translated { key: String, language: String },
+    /// 缺少翻译的键不再被引用
+    NoLongerReferenced { key: String, language: String },
+    /// 新出现的未使用翻译键
+    Unused { key: String, language: String },
+    /// 未使用的翻译键现在已被使用
+    NowUsed { key: String, language: String },
+    /// 未使用的翻译键已从翻译文件中删除
+    Removed { key: String, language: String },
+}
+
+/// 一次审计发现的问题：缺少翻译和未使用的（键, 语言）
+#[derive(Debug)]
+struct Findings {
+    missing: BTreeSet<(String, String)>,
+    unused: BTreeSet<(String, String)>,
+}
+
+impl Findings {
+    fn from_result(result: &AnalysisResult) -> Self {
+        let missing = result.missing_keys.iter()
+            .flat_map(|k| k.missing_languages.iter().map(|language| (k.key.clone(), language.clone())))
+            .collect();
+        let unused = result.unused_keys.values()
+            .flatten()
+            .map(|k| (k.key.clone(), k.language.clone()))
+            .collect();
+        Findings { missing, unused }
+    }
+
+    /// 与新的审计结果比较；`defined` 为新结果中已定义的（键, 语言）
+    fn diff(&self, new: &Findings, defined: &HashSet<(&str, &str)>) -> Vec<FindingChange> {
+        let is_defined = |(key, language): &(String, String)| defined.contains(&(key.as_str(), language.as_str()));
+        let mut changes = Vec::new();
+
+        for (key, language) in new.missing.difference(&self.missing) {
+            changes.push(FindingChange::Missing { key: key.clone(), language: language.clone() });
+        }
+        for entry in self.missing.difference(&new.missing) {
+            let (key, language) = entry.clone();
+            changes.push(if is_defined(entry) {
+                FindingChange::Translated { key, language }
+            } else {
+                FindingChange::NoLongerReferenced { key, language }
+            });
+        }
+        for (key, language) in new.unused.difference(&self.unused) {
+            changes.push(FindingChange::Unused { key: key.clone(), language: language.clone() });
+        }
+        for entry in self.unused.difference(&new.unused) {
+            let (key, language) = entry.clone();
+            changes.push(if is_defined(entry) {
+                FindingChange::NowUsed { key, language }
+            } else {
+                FindingChange::Removed { key, language }
+            });
+        }
+
+        changes
+    }
+}
+
+/// 监视项目文件，每次变化后输出审计结果的变化，直到进程被中断
+pub fn run(config: Config) -> Result<()> {
+    let mut workspace = Workspace::load(config)?;
+    let mut result = workspace.analyze()?;
+    let mut stdout = std::io::stdout();
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).context("无法创建文件监视器")?;
+    for path in watched_paths(&workspace.config) {
+        watcher.watch(&path, RecursiveMode::Recursive)
+            .with_context(|| format!("无法监视: {}", path.display()))?;
+        println!("正在监视: {}", path.display());
+    }
+    report::print_watch_summary(&mut stdout, &result)?;
+
+    while let Ok(event) = receiver.recv() {
+        // 合并短时间内的多次变化
+        let mut paths = BTreeSet::new();
+        collect_paths(event, &mut paths);
+        while let Ok(event) = receiver.recv_timeout(DEBOUNCE) {
+            collect_paths(event, &mut paths);
+        }
+
+        let changed_files = match update_workspace(&mut workspace, &paths) {
+            Ok(changed_files) if changed_files.is_empty() => continue,
+            Ok(changed_files) => changed_files,
+            Err(e) => {
+                // 文件可能正在编辑中（例如翻译文件暂时无法解析），等待下一次保存
+                eprintln!("{}: {:#}", "错误".red().bold(), e);
+                continue;
+            }
+        };
+
+        let new_result = workspace.analyze()?;
+        let defined: HashSet<(&str, &str)> = workspace.defined_keys.iter()
+            .map(|k| (k.key.as_str(), k.language.as_str()))
+            .collect();
+        let changes = Findings::from_result(&result).diff(&Findings::from_result(&new_result), &defined);
+
+        report::print_findings_diff(&mut stdout, &changed_files, &changes)?;
+        report::print_watch_summary(&mut stdout, &new_result)?;
+        result = new_result;
+    }
+
+    Ok(())
+}
+
+/// 需要监视的目录和文件：源代码、测试等目录，模板目录和翻译文件目录
+fn watched_paths(config: &Config) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let candidates = crate::scanner::source_roots(config).into_iter()
+        .chain(config.template_paths())
+        .chain(config.locales_paths());
+    for path in candidates {
+        if path.exists() && !paths.iter().any(|watched: &PathBuf| path.starts_with(watched)) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// 收集文件变化事件中的路径，忽略只读访问
+fn collect_paths(event: notify::Result<notify::Event>, paths: &mut BTreeSet<PathBuf>) {
+    match event {
+        Ok(event) if !event.kind.is_access() => {
+            debug!("文件变化: {:?} {:?}", event.kind, event.paths);
+            paths.extend(event.paths);
+        }
+        Ok(_) => {}
+        Err(e) => warn!("文件监视出错: {}", e),
+    }
+}
+
+/// 按变化的文件更新项目状态，返回发生变化的相关文件（相对路径）
+fn update_workspace(workspace: &mut Workspace, paths: &BTreeSet<PathBuf>) -> Result<Vec<String>> {
+    let mut changed_files = Vec::new();
+    let mut reload_translations = false;
+
+    for path in paths {
+        if is_temporary_file(path) {
+            continue;
+        }
+        let Some(relative_path) = workspace.relative_path(path) else {
+            continue;
+        };
+
+        if workspace.is_source_file(path) {
+            if path.is_file() {
+                let content = fs::read_to_string(path)
+                    .with_context(|| format!("无法读取文件: {}", path.display()))?;
+                workspace.rescan(path, &content)?;
+            } else if !workspace.remove(path)? {
+                continue;
+            }
+            changed_files.push(relative_path);
+        } else if workspace.is_locale_file(path) {
+            reload_translations = true;
+            changed_files.push(relative_path);
+        }
+    }
+
+    if reload_translations {
+        workspace.reload_translations()?;
+    }
+    Ok(changed_files)
+}
+
+/// 编辑器的临时文件和备份文件
+fn is_temporary_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.') || name.ends_with('~') || name.ends_with(".swp"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(items: &[(&str, &str)]) -> BTreeSet<(String, String)> {
+        items.iter().map(|(key, language)| (key.to_string(), language.to_string())).collect()
+    }
+
+    #[test]
+    fn test_findings_diff() {
+        let old = Findings {
+            missing: entries(&[("user.name", "fr"), ("user.age", "fr")]),
+            unused: entries(&[("menu.file", "en"), ("menu.edit", "en")]),
+        };
+        let new = Findings {
+            missing: entries(&[("user.email", "fr")]),
+            unused: entries(&[("menu.view", "en")]),
+        };
+        let defined: HashSet<(&str, &str)> = [("user.name", "fr"), ("menu.file", "en"), ("menu.view", "en")]
+            .into_iter()
+            .collect();
+
+        let changes = old.diff(&new, &defined);
+        assert_eq!(changes, vec![
+            FindingChange::Missing { key: "user.email".to_string(), language: "fr".to_string() },
+            FindingChange::NoLongerReferenced { key: "user.age".to_string(), language: "fr".to_string() },
+            FindingChange::Translated { key: "user.name".to_string(), language: "fr".to_string() },
+            FindingChange::Unused { key: "menu.view".to_string(), language: "en".to_string() },
+            FindingChange::Removed { key: "menu.edit".to_string(), language: "en".to_string() },
+            FindingChange::NowUsed { key: "menu.file".to_string(), language: "en".to_string() },
+        ]);
+    }
+}
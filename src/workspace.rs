@@ -0,0 +1,165 @@
+//! 常驻进程（语言服务器、监视模式）使用的项目状态
+//!
+//! 启动时扫描整个项目，之后按文件更新：模板修改时只重新扫描该文件；Rust 源文件修改时
+//! 重建扫描上下文（常量、枚举、cfg 模块）并重新扫描所有 Rust 源文件，因为其他文件中的
+//! 键可能引用该文件中的定义；翻译文件修改时重新解析所有翻译文件。
+
+use anyhow::{Result, Context};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::analyzer::{self, AnalysisResult};
+use crate::config::Config;
+use crate::parser::{self, DefinedKey};
+use crate::scanner::{self, SourceIndex, UsedKey};
+
+/// 项目中所有翻译键的使用和定义
+pub struct Workspace {
+    pub config: Config,
+    /// 所有 Rust 源文件（相对路径）的当前内容，用于重建扫描上下文
+    sources: BTreeMap<String, String>,
+    /// 整个 crate 的扫描上下文，随 Rust 源文件的修改重建
+    index: SourceIndex,
+    /// 每个源文件（相对路径）中的翻译键使用
+    usages: BTreeMap<String, Vec<UsedKey>>,
+    pub defined_keys: Vec<DefinedKey>,
+}
+
+impl Workspace {
+    /// 扫描整个项目，项目路径转换为绝对路径以便与文件监视、编辑器传来的路径比较
+    pub fn load(mut config: Config) -> Result<Self> {
+        config.project_path = config.project_path.canonicalize()
+            .with_context(|| format!("无法访问项目目录: {}", config.project_path.display()))?;
+
+        let sources: BTreeMap<String, String> = scanner::read_rust_sources(&config)?.into_iter().collect();
+        let defined_keys = parser::parse_translation_files(&config).context("解析翻译文件失败")?;
+        let mut workspace = Workspace {
+            index: SourceIndex::new(&config, &[]),
+            config,
+            sources,
+            usages: BTreeMap::new(),
+            defined_keys,
+        };
+        workspace.rescan_rust_sources()?;
+        for (relative_path, content) in scanner::read_template_sources(&workspace.config)? {
+            let used_keys = scanner::scan_template_file(&relative_path, &content)?;
+            workspace.usages.insert(relative_path, used_keys);
+        }
+        Ok(workspace)
+    }
+
+    /// 用当前的 Rust 源文件重建扫描上下文，并重新扫描所有 Rust 源文件
+    fn rescan_rust_sources(&mut self) -> Result<()> {
+        let sources: Vec<(String, String)> = self.sources.iter()
+            .map(|(path, content)| (path.clone(), content.clone()))
+            .collect();
+        self.index = SourceIndex::new(&self.config, &sources);
+        for (relative_path, content) in &sources {
+            let used_keys = scanner::scan_rust_file(relative_path, content, &self.index)?;
+            self.usages.insert(relative_path.clone(), used_keys);
+        }
+        Ok(())
+    }
+
+    /// 所有文件中的翻译键使用
+    pub fn used_keys(&self) -> impl Iterator<Item = &UsedKey> {
+        self.usages.values().flatten()
+    }
+
+    /// 用当前的使用和定义进行分析
+    pub fn analyze(&self) -> Result<AnalysisResult> {
+        let used_keys: Vec<UsedKey> = self.used_keys().cloned().collect();
+        analyzer::analyze(&used_keys, &self.defined_keys, &self.config).context("分析翻译键使用情况失败")
+    }
+
+    /// 文件相对于项目根目录的路径
+    pub fn relative_path(&self, path: &Path) -> Option<String> {
+        path.strip_prefix(&self.config.project_path).ok()
+            .map(|relative| relative.to_string_lossy().to_string())
+    }
+
+    /// 是否为需要扫描的 Rust 源文件或模板文件
+    pub fn is_source_file(&self, path: &Path) -> bool {
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+        if extension == "rs" {
+            scanner::source_roots(&self.config).iter().any(|root| path.starts_with(root))
+        } else {
+            scanner::TEMPLATE_FILE_EXTENSIONS.contains(&extension)
+                && self.config.template_paths().iter().any(|dir| path.starts_with(dir))
+        }
+    }
+
+    /// 是否为翻译文件目录中的文件
+    pub fn is_locale_file(&self, path: &Path) -> bool {
+        self.config.locales_paths().iter().any(|dir| path.starts_with(dir))
+    }
+
+    /// 用文件的新内容重新扫描一个源文件或模板文件；返回 false 表示该文件不需要扫描
+    pub fn rescan(&mut self, path: &Path, content: &str) -> Result<bool> {
+        if !self.is_source_file(path) {
+            return Ok(false);
+        }
+        let Some(relative_path) = self.relative_path(path) else {
+            return Ok(false);
+        };
+
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            if self.sources.get(&relative_path).is_some_and(|old| old == content) {
+                return Ok(true);
+            }
+            self.sources.insert(relative_path, content.to_string());
+            self.rescan_rust_sources()?;
+        } else {
+            let used_keys = scanner::scan_template_file(&relative_path, content)?;
+            self.usages.insert(relative_path, used_keys);
+        }
+        Ok(true)
+    }
+
+    /// 移除已删除的文件中的使用；返回该文件之前是否被扫描过
+    pub fn remove(&mut self, path: &Path) -> Result<bool> {
+        let Some(relative_path) = self.relative_path(path) else {
+            return Ok(false);
+        };
+        if self.sources.remove(&relative_path).is_some() {
+            self.usages.remove(&relative_path);
+            self.rescan_rust_sources()?;
+            return Ok(true);
+        }
+        Ok(self.usages.remove(&relative_path).is_some())
+    }
+
+    /// 重新解析所有翻译文件
+    pub fn reload_translations(&mut self) -> Result<()> {
+        self.defined_keys = parser::parse_translation_files(&self.config).context("解析翻译文件失败")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    use crate::audit::AuditBuilder;
+
+    #[test]
+    fn test_rescan_rebuilds_constants() {
+        let project = tempfile::tempdir().unwrap();
+        fs::create_dir_all(project.path().join("src")).unwrap();
+        fs::write(project.path().join("src/keys.rs"), "pub const TITLE: &str = \"menu.title\";\n").unwrap();
+        fs::write(project.path().join("src/main.rs"), "mod keys;\nfn main() { t!(keys::TITLE); }\n").unwrap();
+        let config = AuditBuilder::new(project.path()).build().unwrap().into_config();
+        let mut workspace = Workspace::load(config).unwrap();
+        let keys = |workspace: &Workspace| workspace.used_keys().map(|u| u.key.clone()).collect::<Vec<_>>();
+        assert_eq!(keys(&workspace), ["menu.title"]);
+
+        // 修改常量后，引用它的其他文件中的使用随之更新
+        let keys_file = workspace.config.project_path.join("src/keys.rs");
+        assert!(workspace.rescan(&keys_file, "pub const TITLE: &str = \"menu.heading\";\n").unwrap());
+        assert_eq!(keys(&workspace), ["menu.heading"]);
+
+        assert!(workspace.remove(&keys_file).unwrap());
+        assert!(keys(&workspace).is_empty());
+    }
+}
@@ -0,0 +1,525 @@
+use anyhow::{Result, Context, bail};
+use log::{info, warn};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::escape::escape;
+use quick_xml::{Reader, XmlVersion};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+
+use crate::config::Config;
+use crate::locale_writer::{self, KeyUpdate};
+use crate::parser::DefinedKey;
+
+/// XLIFF 版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XliffVersion {
+    V1_2,
+    V2_0,
+}
+
+impl std::str::FromStr for XliffVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "1.2" => Ok(XliffVersion::V1_2),
+            "2.0" | "2" => Ok(XliffVersion::V2_0),
+            _ => bail!("不支持的 XLIFF 版本: {}，可选值: 1.2, 2.0", s),
+        }
+    }
+}
+
+/// 翻译单元的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitState {
+    /// 目标语言中缺少翻译
+    Missing,
+    /// 目标语言的翻译与源语言相同，需要复核
+    NeedsReview,
+    /// 已翻译
+    Translated,
+}
+
+impl UnitState {
+    /// XLIFF 1.2 中 `<target state="...">` 的取值
+    fn as_v1_2(self) -> &'static str {
+        match self {
+            UnitState::Missing => "needs-translation",
+            UnitState::NeedsReview => "needs-review-translation",
+            UnitState::Translated => "translated",
+        }
+    }
+
+    /// XLIFF 2.0 中 `<segment state="...">` 的取值
+    fn as_v2_0(self) -> &'static str {
+        match self {
+            UnitState::Missing => "initial",
+            UnitState::NeedsReview | UnitState::Translated => "translated",
+        }
+    }
+
+    /// 从 XLIFF 的状态属性解析
+    fn from_attr(state: &str, sub_state: Option<&str>) -> Self {
+        match state {
+            "new" | "needs-translation" | "initial" => UnitState::Missing,
+            "needs-review-translation" | "needs-review-l10n" | "needs-review-adaptation"
+            | "needs-adaptation" | "needs-l10n" => UnitState::NeedsReview,
+            _ if sub_state == Some(NEEDS_REVIEW_SUBSTATE) => UnitState::NeedsReview,
+            _ => UnitState::Translated,
+        }
+    }
+}
+
+/// XLIFF 2.0 没有“待复核”状态，使用自定义的 subState 表示
+const NEEDS_REVIEW_SUBSTATE: &str = "i18n-audit:needs-review";
+
+/// XLIFF 文件中的一个翻译单元
+#[derive(Debug, Clone)]
+pub struct XliffUnit {
+    /// 翻译键
+    pub id: String,
+    /// 源语言文本
+    pub source: String,
+    /// 目标语言文本
+    pub target: Option<String>,
+    /// 状态
+    pub state: UnitState,
+}
+
+/// 解析后的 XLIFF 文档
+#[derive(Debug, Clone)]
+pub struct XliffDocument {
+    /// 源语言
+    pub source_language: String,
+    /// 目标语言
+    pub target_language: String,
+    /// 翻译单元
+    pub units: Vec<XliffUnit>,
+}
+
+/// 导出默认语言的所有翻译键及目标语言的当前翻译为 XLIFF
+pub fn export(
+    defined_keys: &[DefinedKey],
+    source_language: &str,
+    target_language: &str,
+    version: XliffVersion,
+) -> Result<String> {
+    let source_keys: BTreeMap<&str, &DefinedKey> = defined_keys.iter()
+        .filter(|k| k.language == source_language)
+        .map(|k| (k.key.as_str(), k))
+        .collect();
+
+    if source_keys.is_empty() {
+        bail!("默认语言 {} 中没有任何翻译键", source_language);
+    }
+
+    let target_values: HashMap<&str, &str> = defined_keys.iter()
+        .filter(|k| k.language == target_language)
+        .map(|k| (k.key.as_str(), k.value.as_str()))
+        .collect();
+
+    let mut xml = String::new();
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+
+    match version {
+        XliffVersion::V1_2 => {
+            writeln!(xml, r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">"#)?;
+            writeln!(
+                xml,
+                r#"  <file original="i18n-audit" source-language="{}" target-language="{}" datatype="plaintext">"#,
+                escape(source_language),
+                escape(target_language)
+            )?;
+            writeln!(xml, "    <body>")?;
+        }
+        XliffVersion::V2_0 => {
+            writeln!(
+                xml,
+                r#"<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="{}" trgLang="{}">"#,
+                escape(source_language),
+                escape(target_language)
+            )?;
+            writeln!(xml, r#"  <file id="i18n-audit">"#)?;
+        }
+    }
+
+    for (key, source_key) in &source_keys {
+        let target = target_values.get(key).copied();
+        let state = match target {
+            None => UnitState::Missing,
+            Some(value) if value == source_key.value => UnitState::NeedsReview,
+            Some(_) => UnitState::Translated,
+        };
+
+        match version {
+            XliffVersion::V1_2 => {
+                writeln!(xml, r#"      <trans-unit id="{}" resname="{}">"#, escape(*key), escape(*key))?;
+                writeln!(xml, "        <source>{}</source>", escape(&source_key.value))?;
+                writeln!(
+                    xml,
+                    r#"        <target state="{}">{}</target>"#,
+                    state.as_v1_2(),
+                    escape(target.unwrap_or_default())
+                )?;
+                writeln!(xml, "        <note>{}</note>", escape(&source_key.file_path))?;
+                writeln!(xml, "      </trans-unit>")?;
+            }
+            XliffVersion::V2_0 => {
+                writeln!(xml, r#"    <unit id="{}">"#, escape(*key))?;
+                writeln!(xml, "      <notes>")?;
+                writeln!(xml, "        <note>{}</note>", escape(&source_key.file_path))?;
+                writeln!(xml, "      </notes>")?;
+                if state == UnitState::NeedsReview {
+                    writeln!(xml, r#"      <segment state="{}" subState="{}">"#, state.as_v2_0(), NEEDS_REVIEW_SUBSTATE)?;
+                } else {
+                    writeln!(xml, r#"      <segment state="{}">"#, state.as_v2_0())?;
+                }
+                writeln!(xml, "        <source>{}</source>", escape(&source_key.value))?;
+                if let Some(value) = target {
+                    writeln!(xml, "        <target>{}</target>", escape(value))?;
+                }
+                writeln!(xml, "      </segment>")?;
+                writeln!(xml, "    </unit>")?;
+            }
+        }
+    }
+
+    match version {
+        XliffVersion::V1_2 => {
+            writeln!(xml, "    </body>")?;
+            writeln!(xml, "  </file>")?;
+        }
+        XliffVersion::V2_0 => {
+            writeln!(xml, "  </file>")?;
+        }
+    }
+    writeln!(xml, "</xliff>")?;
+
+    info!("导出 {} 个翻译单元 ({} -> {})", source_keys.len(), source_language, target_language);
+
+    Ok(xml)
+}
+
+/// 读取元素的某个属性值
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>> {
+    for attr in element.attributes() {
+        let attr = attr?;
+        if attr.key.local_name().as_ref() == name {
+            return Ok(Some(attr.normalized_value(XmlVersion::Implicit1_0)?.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// 解析 XLIFF 1.2 或 2.0 文档，两种版本的结构差异在解析时统一处理
+pub fn parse(content: &str) -> Result<XliffDocument> {
+    let mut reader = Reader::from_str(content);
+
+    let mut is_xliff = false;
+    let mut source_language = String::new();
+    let mut target_language = String::new();
+    let mut units = Vec::new();
+
+    // 当前正在解析的翻译单元
+    let mut current: Option<XliffUnit> = None;
+    // 当前正在读取的文本元素 (source / target)
+    let mut text_element: Option<String> = None;
+    let mut text = String::new();
+    // XLIFF 2.0 的状态位于 <segment> 上
+    let mut segment_state: Option<(String, Option<String>)> = None;
+
+    loop {
+        let event = reader.read_event().context("无法解析 XLIFF 文件")?;
+        let is_empty = matches!(event, Event::Empty(_));
+        
+        match event {
+            Event::Start(element) | Event::Empty(element) if text_element.is_none() => {
+                match element.local_name().as_ref() {
+                    "xliff" => {
+                        is_xliff = true;
+                        if let Some(lang) = attribute(&element, "srcLang")? {
+                            source_language = lang;
+                        }
+                        if let Some(lang) = attribute(&element, "trgLang")? {
+                            target_language = lang;
+                        }
+                    }
+                    "file" => {
+                        if let Some(lang) = attribute(&element, "source-language")? {
+                            source_language = lang;
+                        }
+                        if let Some(lang) = attribute(&element, "target-language")? {
+                            target_language = lang;
+                        }
+                    }
+                    "trans-unit" | "unit" => {
+                        let id = attribute(&element, "resname")?
+                            .or(attribute(&element, "id")?)
+                            .unwrap_or_default();
+                        current = Some(XliffUnit {
+                            id,
+                            source: String::new(),
+                            target: None,
+                            state: UnitState::Translated,
+                        });
+                        segment_state = None;
+                    }
+                    "segment" => {
+                        if let Some(state) = attribute(&element, "state")? {
+                            segment_state = Some((state, attribute(&element, "subState")?));
+                        }
+                    }
+                    name @ ("source" | "target") if current.is_some() => {
+                        if name == "target"
+                            && let Some(state) = attribute(&element, "state")?
+                            && let Some(unit) = current.as_mut()
+                        {
+                            unit.state = UnitState::from_attr(&state, None);
+                        }
+                        if is_empty {
+                            if name == "target"
+                                && let Some(unit) = current.as_mut()
+                            {
+                                unit.target = Some(String::new());
+                            }
+                        } else {
+                            text.clear();
+                            text_element = Some(name.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(t) if text_element.is_some() => {
+                text.push_str(&t.xml10_content());
+            }
+            Event::GeneralRef(r) if text_element.is_some() => {
+                if let Some(ch) = r.resolve_char_ref()? {
+                    text.push(ch);
+                } else {
+                    let name = r.xml10_content();
+                    match quick_xml::escape::resolve_predefined_entity(&name) {
+                        Some(resolved) => text.push_str(resolved),
+                        None => bail!("XLIFF 中存在无法识别的实体: &{};", name),
+                    }
+                }
+            }
+            Event::CData(t) if text_element.is_some() => {
+                text.push_str(&t.xml10_content());
+            }
+            Event::End(element) => {
+                let name = element.local_name();
+                if text_element.as_deref() == Some(name.as_ref()) {
+                    if let Some(unit) = current.as_mut() {
+                        match name.as_ref() {
+                            "source" => unit.source = text.clone(),
+                            _ => unit.target = Some(text.clone()),
+                        }
+                    }
+                    text_element = None;
+                } else if matches!(name.as_ref(), "trans-unit" | "unit")
+                    && let Some(mut unit) = current.take()
+                {
+                    if let Some((state, sub_state)) = segment_state.take() {
+                        unit.state = UnitState::from_attr(&state, sub_state.as_deref());
+                    }
+                    if unit.target.as_deref().is_none_or(|t| t.is_empty()) {
+                        unit.state = UnitState::Missing;
+                    }
+                    units.push(unit);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !is_xliff {
+        bail!("不是有效的 XLIFF 文件: 缺少 <xliff> 根元素");
+    }
+
+    Ok(XliffDocument {
+        source_language,
+        target_language,
+        units,
+    })
+}
+
+/// XLIFF 导入结果
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    /// 写入的翻译数量
+    pub updated: usize,
+    /// 因未翻译而跳过的翻译单元数量
+    pub skipped: usize,
+    /// 默认语言中不存在的翻译键
+    pub unknown_keys: Vec<String>,
+}
+
+/// 将 XLIFF 中已翻译的内容合并回翻译文件，不改动其他键
+///
+/// 所有翻译单元都能写入后才修改文件，任一单元无法写入时不修改任何文件。
+pub fn import(
+    document: &XliffDocument,
+    language: &str,
+    defined_keys: &[DefinedKey],
+    config: &Config,
+) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+    let mut updates = Vec::new();
+
+    for unit in &document.units {
+        let Some(target) = unit.target.as_deref() else {
+            summary.skipped += 1;
+            continue;
+        };
+        if unit.state == UnitState::Missing {
+            summary.skipped += 1;
+            continue;
+        }
+
+        if !defined_keys.iter().any(|k| k.language == config.default_locale && k.key == unit.id) {
+            warn!("默认语言 {} 中不存在翻译键 {}，已跳过", config.default_locale, unit.id);
+            summary.unknown_keys.push(unit.id.clone());
+            continue;
+        }
+
+        // 值未变化时无需写入
+        if defined_keys.iter().any(|k| k.language == language && k.key == unit.id && k.value == target) {
+            continue;
+        }
+
+        updates.push(KeyUpdate {
+            path: locale_writer::locate_target_file(&unit.id, language, defined_keys, config),
+            language,
+            key: &unit.id,
+            value: target,
+        });
+    }
+
+    locale_writer::set_keys(&updates)?;
+    summary.updated = updates.len();
+
+    info!("导入完成: 更新 {} 个翻译，跳过 {} 个未翻译单元", summary.updated, summary.skipped);
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditBuilder;
+    use std::fs;
+
+    fn defined(key: &str, language: &str, value: &str) -> DefinedKey {
+        DefinedKey {
+            key: key.to_string(),
+            language: language.to_string(),
+            value: value.to_string(),
+            file_path: format!("locales/{}.yml", language),
+        }
+    }
+
+    #[test]
+    fn test_export_and_parse_round_trip() {
+        let defined_keys = vec![
+            defined("greetings.hello", "en", "Hello & welcome"),
+            defined("greetings.goodbye", "en", "Goodbye"),
+            defined("brand", "en", "Acme"),
+            defined("greetings.hello", "fr", "Bonjour & bienvenue"),
+            defined("brand", "fr", "Acme"),
+        ];
+
+        for version in [XliffVersion::V1_2, XliffVersion::V2_0] {
+            let xml = export(&defined_keys, "en", "fr", version).unwrap();
+            let document = parse(&xml).unwrap();
+
+            assert_eq!(document.source_language, "en");
+            assert_eq!(document.target_language, "fr");
+
+            let states: Vec<(&str, UnitState)> = document.units.iter()
+                .map(|u| (u.id.as_str(), u.state))
+                .collect();
+            assert_eq!(states, vec![
+                ("brand", UnitState::NeedsReview),
+                ("greetings.goodbye", UnitState::Missing),
+                ("greetings.hello", UnitState::Translated),
+            ]);
+            assert_eq!(document.units[2].source, "Hello & welcome");
+            assert_eq!(document.units[2].target.as_deref(), Some("Bonjour & bienvenue"));
+        }
+    }
+
+    fn unit(id: &str, target: Option<&str>, state: UnitState) -> XliffUnit {
+        XliffUnit {
+            id: id.to_string(),
+            source: String::new(),
+            target: target.map(String::from),
+            state,
+        }
+    }
+
+    fn document(units: Vec<XliffUnit>) -> XliffDocument {
+        XliffDocument {
+            source_language: "en".to_string(),
+            target_language: "fr".to_string(),
+            units,
+        }
+    }
+
+    #[test]
+    fn test_import_merges_translated_units() {
+        let project = tempfile::tempdir().unwrap();
+        fs::create_dir_all(project.path().join("locales")).unwrap();
+        fs::write(project.path().join("locales/fr.yml"), "# Traductions\ngreetings:\n  hello: Salut\n").unwrap();
+        let config = AuditBuilder::new(project.path()).build().unwrap().into_config();
+
+        let defined_keys = vec![
+            defined("greetings.hello", "en", "Hello"),
+            defined("greetings.goodbye", "en", "Goodbye"),
+            defined("brand", "en", "Acme"),
+            defined("greetings.hello", "fr", "Salut"),
+        ];
+        let document = document(vec![
+            unit("greetings.hello", Some("Bonjour"), UnitState::Translated),
+            unit("greetings.goodbye", Some("Au revoir"), UnitState::Translated),
+            unit("brand", None, UnitState::Missing),
+            unit("removed.key", Some("Supprimé"), UnitState::Translated),
+        ]);
+
+        let summary = import(&document, "fr", &defined_keys, &config).unwrap();
+        assert_eq!(summary.updated, 2);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.unknown_keys, vec!["removed.key"]);
+        assert_eq!(
+            fs::read_to_string(project.path().join("locales/fr.yml")).unwrap(),
+            "# Traductions\ngreetings:\n  hello: Bonjour\n  goodbye: Au revoir\n"
+        );
+    }
+
+    #[test]
+    fn test_import_writes_nothing_when_a_unit_fails() {
+        let project = tempfile::tempdir().unwrap();
+        fs::create_dir_all(project.path().join("locales")).unwrap();
+        fs::write(project.path().join("locales/fr.yml"), "hello: Salut\n").unwrap();
+        fs::write(project.path().join("locales/fr.ftl"), "login-input = Connexion\n    .placeholder = Nom\n").unwrap();
+        let config = AuditBuilder::new(project.path()).build().unwrap().into_config();
+
+        let mut defined_keys = vec![
+            defined("hello", "en", "Hello"),
+            defined("login-input.placeholder", "en", "Name"),
+            defined("hello", "fr", "Salut"),
+        ];
+        defined_keys.push(DefinedKey {
+            file_path: "locales/fr.ftl".to_string(),
+            ..defined("login-input.placeholder", "fr", "Nom")
+        });
+        let document = document(vec![
+            unit("hello", Some("Bonjour"), UnitState::Translated),
+            unit("login-input.placeholder", Some("Identifiant"), UnitState::Translated),
+        ]);
+
+        assert!(import(&document, "fr", &defined_keys, &config).is_err());
+        assert_eq!(fs::read_to_string(project.path().join("locales/fr.yml")).unwrap(), "hello: Salut\n");
+    }
+}